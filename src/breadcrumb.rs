@@ -0,0 +1,34 @@
+use std::time::SystemTime;
+
+use crate::report::Breadcrumb;
+use crate::ring::Ring;
+
+static TRAIL: Ring<(SystemTime, String)> = Ring::new(32);
+
+/// Record an event to include in the next report
+///
+/// Only the most recent events are kept, see
+/// [`PanicHandler::breadcrumbs`][crate::PanicHandler::breadcrumbs].
+///
+/// ## Example
+///
+/// ```rust
+/// let project = "website";
+/// human_panic::breadcrumb(format!("opened project {project}"));
+/// ```
+pub fn breadcrumb(message: impl Into<String>) {
+    if TRAIL.is_enabled() {
+        TRAIL.push((SystemTime::now(), message.into()));
+    }
+}
+
+pub(crate) fn set_capacity(capacity: usize) {
+    TRAIL.set_capacity(capacity);
+}
+
+/// The recorded events, oldest first
+pub(crate) fn trail() -> Vec<Breadcrumb> {
+    TRAIL.collect(|(time, message)| {
+        Breadcrumb::new(crate::time::rfc3339_millis(*time), message.clone())
+    })
+}
@@ -0,0 +1,197 @@
+//! Build provenance, like the compiler and git commit a binary came from
+//!
+//! To record this in reports, call [`emit`] from your build script:
+//!
+//! ```toml
+//! [build-dependencies]
+//! human-panic = "2"
+//! ```
+//!
+//! ```rust,no_run
+//! // In build.rs's `main`
+//! human_panic::build::emit();
+//! ```
+//!
+//! [`metadata!`][crate::metadata!] then picks it up.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Capture build provenance for [`metadata!`][crate::metadata!]
+///
+/// This must be called from a build script.
+///
+/// The build script re-runs when the package's `src` or `Cargo.toml` change, or when git's `HEAD`,
+/// current branch, or index change.  Uncommitted edits elsewhere may leave the git state stale.
+#[allow(clippy::print_stdout)] // build script protocol
+pub fn emit() {
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let rustc_version = command_output(Command::new(rustc).arg("--version"));
+    let features = {
+        let features = env::var("CARGO_CFG_FEATURE").unwrap_or_default();
+        let mut features = features
+            .split(',')
+            .filter(|f| !f.is_empty())
+            .collect::<Vec<_>>();
+        features.sort();
+        features.join(",")
+    };
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_else(|| ".".into());
+    let manifest_dir = Path::new(&manifest_dir);
+    let git = |args: &[&str]| {
+        command_output(
+            Command::new("git")
+                .arg("--no-optional-locks")
+                .args(args)
+                .current_dir(manifest_dir),
+        )
+    };
+    let git_commit = git(&["rev-parse", "HEAD"]);
+    let git_dirty = git_commit
+        .as_ref()
+        .and_then(|_| git(&["status", "--porcelain"]))
+        .map(|status| !status.is_empty());
+
+    // Replace Cargo's default of re-running for any change in the package
+    let mut rerun_paths = vec![manifest_dir.join("src"), manifest_dir.join("Cargo.toml")];
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        let git_dir = manifest_dir.join(git_dir);
+        let head_ref = git(&["symbolic-ref", "--quiet", "HEAD"]);
+        rerun_paths.extend(
+            ["HEAD", "index", "packed-refs"]
+                .into_iter()
+                .chain(head_ref.as_deref())
+                .map(|path| git_dir.join(path)),
+        );
+    }
+
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let workspace_root = command_output(
+        Command::new(cargo)
+            .arg("locate-project")
+            .arg("--workspace")
+            .arg("--message-format=plain")
+            .current_dir(manifest_dir),
+    )
+    .and_then(|manifest| Some(Path::new(&manifest).parent()?.to_str()?.to_owned()));
+
+    let vars = [
+        ("HUMAN_PANIC_RUSTC_VERSION", rustc_version),
+        ("HUMAN_PANIC_TARGET", env::var("TARGET").ok()),
+        ("HUMAN_PANIC_PROFILE", env::var("PROFILE").ok()),
+        ("HUMAN_PANIC_OPT_LEVEL", env::var("OPT_LEVEL").ok()),
+        ("HUMAN_PANIC_FEATURES", Some(features)),
+        ("HUMAN_PANIC_GIT_COMMIT", git_commit),
+        ("HUMAN_PANIC_GIT_DIRTY", git_dirty.map(|d| d.to_string())),
+        ("HUMAN_PANIC_WORKSPACE_ROOT", workspace_root),
+    ];
+    for (key, value) in vars {
+        println!("cargo:rustc-env={key}={}", value.unwrap_or_default());
+    }
+    // Missing paths would re-run the build script on every build
+    for path in rerun_paths.iter().filter(|path| path.exists()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
+
+fn command_output(cmd: &mut Command) -> Option<String> {
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_owned())
+}
+
+/// Build provenance, see [`emit`]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Build {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rustc_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opt_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    features: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    git_dirty: Option<bool>,
+}
+
+impl Build {
+    /// Used by [`metadata!`][crate::metadata!] with the variables set by [`emit`]
+    #[doc(hidden)]
+    pub fn from_env(
+        rustc_version: &str,
+        target: &str,
+        profile: &str,
+        opt_level: &str,
+        features: &str,
+        git_commit: &str,
+        git_dirty: &str,
+    ) -> Self {
+        Self {
+            rustc_version: non_empty(rustc_version),
+            target: non_empty(target),
+            profile: non_empty(profile),
+            opt_level: non_empty(opt_level),
+            features: features
+                .split(',')
+                .filter(|f| !f.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+            git_commit: non_empty(git_commit),
+            git_dirty: git_dirty.parse().ok(),
+        }
+    }
+
+    /// The output of `rustc --version`
+    pub fn rustc_version(&self) -> Option<&str> {
+        self.rustc_version.as_deref()
+    }
+
+    /// The target triple
+    pub fn target(&self) -> Option<&str> {
+        self.target.as_deref()
+    }
+
+    /// The Cargo profile, like `debug` or `release`
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// The optimization level
+    pub fn opt_level(&self) -> Option<&str> {
+        self.opt_level.as_deref()
+    }
+
+    /// The enabled Cargo features
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// The git commit hash
+    pub fn git_commit(&self) -> Option<&str> {
+        self.git_commit.as_deref()
+    }
+
+    /// Whether the git working tree had uncommitted changes
+    pub fn git_dirty(&self) -> Option<bool> {
+        self.git_dirty
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_owned())
+}
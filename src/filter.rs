@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use crate::report::Frame;
+
+type FramePredicate = dyn Fn(&Frame) -> bool + Send + Sync + 'static;
+
+/// Hides uninteresting frames of a [`Report`][crate::report::Report]'s backtrace
+///
+/// By default, this keeps only the frames between the panic machinery and the Rust runtime, like
+/// the short backtraces printed by `std` with `RUST_BACKTRACE=1`.
+///
+/// ## Example
+///
+/// ```rust
+/// use human_panic::report::FrameFilter;
+///
+/// let filter = FrameFilter::new()
+///     .hide_crate("tokio")
+///     .hide_module("my_app::runtime")
+///     .keep_full(true);
+/// ```
+#[derive(Clone)]
+pub struct FrameFilter {
+    short: bool,
+    prefixes: Vec<String>,
+    predicates: Vec<Arc<FramePredicate>>,
+    keep_full: bool,
+}
+
+impl FrameFilter {
+    /// Hide the panic machinery and Rust runtime
+    pub fn new() -> Self {
+        Self {
+            short: true,
+            prefixes: Vec::new(),
+            predicates: Vec::new(),
+            keep_full: false,
+        }
+    }
+
+    /// Hide the frames outside of `__rust_end_short_backtrace` and `__rust_begin_short_backtrace`
+    ///
+    /// This also hides the panic entry points, like `core::panicking::panic_fmt`, and closure shims.
+    pub fn short(mut self, yes: bool) -> Self {
+        self.short = yes;
+        self
+    }
+
+    /// Hide frames from the crate `name`
+    pub fn hide_crate(self, name: &str) -> Self {
+        self.hide_module(&name.replace('-', "_"))
+    }
+
+    /// Hide frames from the module `path`, including its submodules
+    pub fn hide_module(mut self, path: &str) -> Self {
+        self.prefixes.push(format!("{path}::"));
+        self
+    }
+
+    /// Hide frames for which `predicate` returns `true`
+    ///
+    /// See [callbacks][crate::PanicHandler#callbacks].
+    pub fn hide(mut self, predicate: impl Fn(&Frame) -> bool + Send + Sync + 'static) -> Self {
+        self.predicates.push(Arc::new(predicate));
+        self
+    }
+
+    /// Keep hidden frames in the report, marked with [`Frame::is_hidden`]
+    ///
+    /// By default, hidden frames are removed.
+    pub fn keep_full(mut self, yes: bool) -> Self {
+        self.keep_full = yes;
+        self
+    }
+
+    pub(crate) fn is_keep_full(&self) -> bool {
+        self.keep_full
+    }
+
+    /// Which of `frames` to hide
+    pub(crate) fn hidden(&self, frames: &[Frame]) -> Vec<bool> {
+        let symbols = frames
+            .iter()
+            .map(|frame| frame.name().map(crate::report::normalize_symbol))
+            .collect::<Vec<_>>();
+        let short = if self.short {
+            short_range(&symbols)
+        } else {
+            0..frames.len()
+        };
+        frames
+            .iter()
+            .zip(&symbols)
+            .enumerate()
+            .map(|(i, (frame, symbol))| {
+                let symbol = symbol.as_deref().unwrap_or("");
+                let is_shim = symbol.ends_with("{{vtable.shim}}") || in_module(symbol, SHIMS);
+                !short.contains(&i)
+                    || (self.short && is_shim)
+                    || in_module(symbol, &self.prefixes)
+                    || self.predicates.iter().any(|predicate| predicate(frame))
+            })
+            .collect()
+    }
+}
+
+impl Default for FrameFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Calls through closures and function pointers
+const SHIMS: &[&str] = &["core::ops::function::"];
+
+/// Whether `symbol` is within one of the modules, given as `prefixes` ending in `::`
+fn in_module(symbol: &str, prefixes: &[impl AsRef<str>]) -> bool {
+    // Match both the type and the trait of `<Type as Trait>::method`
+    symbol.trim_start_matches('<').split(" as ").any(|path| {
+        prefixes
+            .iter()
+            .any(|prefix| path.starts_with(prefix.as_ref()))
+    })
+}
+
+/// Symbols between the failure and the code that caused it, like the panic entry points
+const FAILURE_ENTRY: &[&str] = &[
+    "backtrace::",
+    "human_panic::",
+    "rust_begin_unwind",
+    "__rustc::rust_begin_unwind",
+    "core::panicking::",
+    "std::panicking::",
+];
+
+/// Frames between the short backtrace markers, without the failure entry points
+fn short_range(symbols: &[Option<String>]) -> std::ops::Range<usize> {
+    let is_marker = |i: usize, marker: &str| {
+        symbols[i].as_deref().is_some_and(|symbol| {
+            // Ignore generic arguments, like `__rust_end_short_backtrace::<F, T>`
+            let path = symbol.split_once("::<").map(|(path, _)| path);
+            path.unwrap_or(symbol).ends_with(marker)
+        })
+    };
+    let mut start = (0..symbols.len())
+        .find(|&i| is_marker(i, "__rust_end_short_backtrace"))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let end = (start..symbols.len())
+        .find(|&i| is_marker(i, "__rust_begin_short_backtrace"))
+        .unwrap_or(symbols.len());
+    while start < end
+        && symbols[start]
+            .as_deref()
+            .is_some_and(|symbol| FAILURE_ENTRY.iter().any(|entry| symbol.starts_with(entry)))
+    {
+        start += 1;
+    }
+    start..end
+}
@@ -17,7 +17,7 @@
 //! ```txt
 //! my-program had a problem and crashed. To help us diagnose the problem you can send us a crash report.
 //!
-//! We have generated a report file at "/var/folders/zw/bpfvmq390lv2c6gn_6byyv0w0000gn/T/report-8351cad6-d2b5-4fe8-accd-1fcbf4538792.toml". Submit an issue or email with the subject of "human-panic Crash Report" and include the report as an attachment.
+//! We have generated a report file at "/var/folders/zw/bpfvmq390lv2c6gn_6byyv0w0000gn/T/report-human-panic-8351cad6-d2b5-4fe8-accd-1fcbf4538792.toml". Submit an issue or email with the subject of "human-panic Crash Report" and include the report as an attachment.
 //!
 //! - Homepage: https://github.com/rust-ci/human-panic
 //! - Authors: Yoshua Wuyts <yoshuawuyts@gmail.com>
@@ -41,12 +41,30 @@
 #[cfg(doctest)]
 pub struct ReadmeDoctests;
 
+mod breadcrumb;
+mod filter;
 mod metadata;
+mod module;
 mod panic;
+mod redact;
+mod remap;
+mod ring;
+mod snippet;
+mod storage;
+mod time;
 
+pub mod build;
+#[cfg(feature = "log")]
+pub mod log;
 pub mod report;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+pub use breadcrumb::breadcrumb;
 pub use metadata::Metadata;
+pub use panic::HookOrder;
+pub use panic::PanicHandler;
 pub use panic::PanicStyle;
+pub use panic::Symbolication;
 pub use panic::handle_dump;
 pub use panic::print_msg;
 pub use panic::setup_panic;
@@ -68,6 +86,17 @@ macro_rules! metadata {
             .authors(env!("CARGO_PKG_AUTHORS").replace(":", ", "))
             .homepage(env!("CARGO_PKG_HOMEPAGE"))
             .repository(env!("CARGO_PKG_REPOSITORY"))
+            .workspace_root(option_env!("HUMAN_PANIC_WORKSPACE_ROOT").unwrap_or(""))
+            .manifest_dir(env!("CARGO_MANIFEST_DIR"))
+            .build($crate::build::Build::from_env(
+                option_env!("HUMAN_PANIC_RUSTC_VERSION").unwrap_or(""),
+                option_env!("HUMAN_PANIC_TARGET").unwrap_or(""),
+                option_env!("HUMAN_PANIC_PROFILE").unwrap_or(""),
+                option_env!("HUMAN_PANIC_OPT_LEVEL").unwrap_or(""),
+                option_env!("HUMAN_PANIC_FEATURES").unwrap_or(""),
+                option_env!("HUMAN_PANIC_GIT_COMMIT").unwrap_or(""),
+                option_env!("HUMAN_PANIC_GIT_DIRTY").unwrap_or(""),
+            ))
     }};
 }
 
@@ -76,6 +105,8 @@ macro_rules! metadata {
 /// The macro should be called from within a function, for example as the first line of the
 /// `main()` function of the program.
 ///
+/// For more control, see [`PanicHandler`].
+///
 /// ## Example
 ///
 /// Default [`metadata!`]
@@ -98,7 +129,7 @@ macro_rules! metadata {
 #[macro_export]
 macro_rules! setup_panic {
     ($meta:expr) => {{
-        $crate::setup_panic(|| $meta);
+        $crate::PanicHandler::new($meta).install();
     }};
 
     () => {
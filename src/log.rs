@@ -0,0 +1,135 @@
+//! Include recent [`log`](https://docs.rs/log) records in reports
+//!
+//! ## Example
+//!
+//! ```rust
+//! use human_panic::log::Logger;
+//! use log::LevelFilter;
+//!
+//! # struct AppLogger;
+//! # impl log::Log for AppLogger {
+//! #     fn enabled(&self, _: &log::Metadata<'_>) -> bool { true }
+//! #     fn log(&self, _: &log::Record<'_>) {}
+//! #     fn flush(&self) {}
+//! # }
+//! Logger::new()
+//!     .forward(AppLogger, LevelFilter::Warn)
+//!     .install()
+//!     .unwrap();
+//!
+//! human_panic::setup_panic!();
+//! ```
+
+use std::time::SystemTime;
+
+use ::log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::ring::Ring;
+
+static RECORDS: Ring<(SystemTime, Level, String, String)> = Ring::new(100);
+
+/// A [`Log`] that keeps the most recent records for the report
+///
+/// Records can also be forwarded to the application's own logger.  Records are kept even when the
+/// application's logger is not verbose enough to show them.
+pub struct Logger {
+    level: LevelFilter,
+    capacity: Option<usize>,
+    forward: Option<(Box<dyn Log>, LevelFilter)>,
+}
+
+impl Logger {
+    /// Keep the 100 most recent [`Level::Debug`] records, without forwarding
+    pub fn new() -> Self {
+        Self {
+            level: LevelFilter::Debug,
+            capacity: None,
+            forward: None,
+        }
+    }
+
+    /// Most verbose level to keep for the report
+    ///
+    /// By default, this is [`LevelFilter::Debug`].
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// How many records to keep for the report
+    ///
+    /// By default, this is 100.
+    pub fn capacity(mut self, count: usize) -> Self {
+        self.capacity = Some(count);
+        self
+    }
+
+    /// Pass records up to `level` on to `logger`
+    pub fn forward(mut self, logger: impl Log + 'static, level: LevelFilter) -> Self {
+        self.forward = Some((Box::new(logger), level));
+        self
+    }
+
+    /// Register as the global logger, see [`log::set_boxed_logger`]
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        if let Some(count) = self.capacity {
+            RECORDS.set_capacity(count);
+        }
+        let forward_level = self
+            .forward
+            .as_ref()
+            .map(|(_, level)| *level)
+            .unwrap_or(LevelFilter::Off);
+        let max_level = self.level.max(forward_level);
+        ::log::set_boxed_logger(Box::new(self))?;
+        ::log::set_max_level(max_level);
+        Ok(())
+    }
+
+    fn forward_to(&self, metadata: &Metadata<'_>) -> Option<&dyn Log> {
+        self.forward
+            .as_ref()
+            .filter(|(logger, level)| metadata.level() <= *level && logger.enabled(metadata))
+            .map(|(logger, _)| logger.as_ref())
+    }
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= self.level || self.forward_to(metadata).is_some()
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if record.level() <= self.level && RECORDS.is_enabled() {
+            RECORDS.push((
+                SystemTime::now(),
+                record.level(),
+                record.target().to_owned(),
+                record.args().to_string(),
+            ));
+        }
+        if let Some(logger) = self.forward_to(record.metadata()) {
+            logger.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some((logger, _)) = &self.forward {
+            logger.flush();
+        }
+    }
+}
+
+/// The recorded records, oldest first
+pub(crate) fn records() -> Vec<String> {
+    RECORDS.collect(|(time, level, target, message)| {
+        let time = crate::time::rfc3339_millis(*time);
+        format!("{time} {level:<5} {target}: {message}")
+    })
+}
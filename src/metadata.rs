@@ -1,4 +1,11 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::panic::{RefUnwindSafe, UnwindSafe};
+
+use crate::build::Build;
+
+type FieldsFn =
+    dyn Fn() -> Vec<(String, String)> + Send + Sync + RefUnwindSafe + UnwindSafe + 'static;
 
 /// A convenient metadata struct that describes a crate
 ///
@@ -10,8 +17,19 @@ pub struct Metadata {
     pub(crate) homepage: Option<Cow<'static, str>>,
     pub(crate) repository: Option<Cow<'static, str>>,
     pub(crate) support: Option<Cow<'static, str>>,
+    pub(crate) build: Build,
+    pub(crate) workspace_root: Option<Cow<'static, str>>,
+    pub(crate) manifest_dir: Option<Cow<'static, str>>,
+    fields: BTreeMap<String, String>,
+    fields_fn: Vec<Box<FieldsFn>>,
 }
 
+// `Metadata` may be held across `catch_unwind`, as it was before it held callbacks
+const _: () = {
+    fn assert_auto_traits<T: Send + Sync + UnwindSafe + RefUnwindSafe>() {}
+    let _ = assert_auto_traits::<Metadata>;
+};
+
 impl Metadata {
     /// See [`metadata!`][crate::metadata!]
     pub fn new(name: impl Into<Cow<'static, str>>, version: impl Into<Cow<'static, str>>) -> Self {
@@ -22,6 +40,11 @@ impl Metadata {
             homepage: None,
             repository: None,
             support: None,
+            build: Build::default(),
+            workspace_root: None,
+            manifest_dir: None,
+            fields: BTreeMap::new(),
+            fields_fn: Vec::new(),
         }
     }
 
@@ -60,4 +83,80 @@ impl Metadata {
         }
         self
     }
+
+    /// Provenance of the build, see [`build::emit`][crate::build::emit]
+    pub fn build(mut self, value: Build) -> Self {
+        self.build = value;
+        self
+    }
+
+    /// Root of the workspace the crate was built in, see [`Remap`][crate::report::Remap]
+    ///
+    /// [`metadata!`][crate::metadata!] sets this from [`build::emit`][crate::build::emit].
+    pub fn workspace_root(mut self, value: impl Into<Cow<'static, str>>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.workspace_root = value.into();
+        }
+        self
+    }
+
+    /// Directory of the crate's `Cargo.toml`, for finding source snippets without
+    /// [`Metadata::workspace_root`]
+    ///
+    /// [`metadata!`][crate::metadata!] sets this from `CARGO_MANIFEST_DIR`.
+    pub fn manifest_dir(mut self, value: impl Into<Cow<'static, str>>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.manifest_dir = value.into();
+        }
+        self
+    }
+
+    /// Include `key = value` in the `[custom]` table of reports
+    pub fn field(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.insert(key.into(), value.into());
+        self
+    }
+
+    /// Include fields computed when the report is created, like the active subcommand
+    ///
+    /// These are added to the `[custom]` table of reports, overriding any [`Metadata::field`] with
+    /// the same key.
+    ///
+    /// See [callbacks][crate::PanicHandler#callbacks].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use human_panic::metadata;
+    ///
+    /// let meta = metadata!()
+    ///     .field("channel", "beta")
+    ///     .fields(|| [("subcommand", std::env::args().nth(1).unwrap_or_default())]);
+    /// ```
+    pub fn fields<F, I, K, V>(mut self, fields: F) -> Self
+    where
+        F: Fn() -> I + Send + Sync + RefUnwindSafe + UnwindSafe + 'static,
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.fields_fn.push(Box::new(move || {
+            fields()
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect()
+        }));
+        self
+    }
+
+    /// Static and dynamic fields, for the `[custom]` table
+    pub(crate) fn custom(&self) -> BTreeMap<String, String> {
+        let mut custom = self.fields.clone();
+        for fields in &self.fields_fn {
+            custom.extend(fields());
+        }
+        custom
+    }
 }
@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+/// A binary or shared library mapped into this process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Module {
+    /// Address the module was loaded at
+    pub(crate) base: usize,
+    pub(crate) path: String,
+}
+
+/// The modules loaded into this process, for finding the one containing an address
+///
+/// This is only known on Linux and Android, from `/proc/self/maps`.
+pub(crate) struct Modules(Vec<(Range<usize>, Module)>);
+
+impl Modules {
+    pub(crate) fn load() -> Self {
+        let maps = if cfg!(any(target_os = "linux", target_os = "android")) {
+            std::fs::read_to_string("/proc/self/maps").unwrap_or_default()
+        } else {
+            String::new()
+        };
+        Self::parse(&maps)
+    }
+
+    /// Parse lines like `55d0c0a00000-55d0c0a10000 r-xp 00010000 08:01 1234    /usr/bin/app`
+    fn parse(maps: &str) -> Self {
+        let mut mappings: Vec<(Range<usize>, String)> = Vec::new();
+        for line in maps.lines() {
+            let mut columns = line.splitn(6, ' ');
+            let (Some(range), Some(path)) = (columns.next(), columns.nth(4)) else {
+                continue;
+            };
+            let path = path.trim_start();
+            // Skip anonymous and pseudo mappings, like `[heap]`
+            if !path.starts_with('/') {
+                continue;
+            }
+            let Some((start, end)) = range.split_once('-') else {
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (
+                usize::from_str_radix(start, 16),
+                usize::from_str_radix(end, 16),
+            ) else {
+                continue;
+            };
+            mappings.push((start..end, path.to_owned()));
+        }
+
+        // A module is mapped in several segments, the lowest being where it was loaded
+        let modules = mappings
+            .iter()
+            .map(|(range, path)| {
+                let base = mappings
+                    .iter()
+                    .filter(|(_, other)| other == path)
+                    .map(|(other, _)| other.start)
+                    .min()
+                    .unwrap_or(range.start);
+                let module = Module {
+                    base,
+                    path: path.clone(),
+                };
+                (range.clone(), module)
+            })
+            .collect();
+        Self(modules)
+    }
+
+    /// The module containing `address`, if known
+    pub(crate) fn find(&self, address: usize) -> Option<&Module> {
+        self.0
+            .iter()
+            .find(|(range, _)| range.contains(&address))
+            .map(|(_, module)| module)
+    }
+}
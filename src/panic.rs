@@ -1,43 +1,470 @@
+use std::error::Error;
 use std::io::Result as IoResult;
-use std::panic::PanicHookInfo;
+use std::panic::{self, PanicHookInfo};
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
 
 use crate::Metadata;
-use crate::report::Report;
+use crate::report::{Directory, Format, FrameFilter, Redactor, Remap, Report, Retention, Storage};
+use crate::report::{count_panic, panic_summary};
 
 #[doc(hidden)]
 pub fn setup_panic(meta: impl Fn() -> Metadata) {
-    #![allow(deprecated)]
+    PanicHandler::new(meta()).install();
+}
+
+type PreviousHook = Box<dyn Fn(&PanicHookInfo<'_>) + Send + Sync + 'static>;
+
+type MessageFn = dyn Fn(Option<&Path>, &Metadata) -> IoResult<()> + Send + Sync + 'static;
+
+/// Configurable panic hook
+///
+/// See [`setup_panic!`][crate::setup_panic!] for the default configuration.
+///
+/// Panics within a second of the previous one, like from other threads failing at the same time,
+/// are summarized in one line and added to the first one's report as
+/// [secondary panics][Report::secondary_panics].  A panic after that, like in a program that
+/// recovers with [`std::panic::catch_unwind`], gets a report of its own.
+///
+/// ## Callbacks
+///
+/// [`PanicHandler::message`], [`Metadata::fields`], [`FrameFilter::hide`], and
+/// [`Redactor::callback`] are called from the panic hook.  A panic within them aborts the process,
+/// possibly before the report is written.
+///
+/// ## Example
+///
+/// ```rust
+/// use human_panic::PanicHandler;
+/// use human_panic::PanicStyle;
+/// use human_panic::metadata;
+///
+/// PanicHandler::new(metadata!())
+///     .style(PanicStyle::Human)
+///     .persist(false)
+///     .install();
+/// ```
+#[derive(Clone)]
+pub struct PanicHandler {
+    meta: Arc<Metadata>,
+    style: Option<PanicStyle>,
+    persist: Option<bool>,
+    message: Option<Arc<MessageFn>>,
+    chain: Option<HookOrder>,
+    breadcrumbs: Option<usize>,
+    filter: Option<FrameFilter>,
+    remap: Option<Remap>,
+    redactor: Option<Redactor>,
+    symbolication: Option<Symbolication>,
+    storage: Storage,
+}
+
+impl PanicHandler {
+    /// Default configuration for the crate described by `meta`
+    pub fn new(meta: Metadata) -> Self {
+        Self {
+            meta: Arc::new(meta),
+            style: None,
+            persist: None,
+            message: None,
+            chain: None,
+            breadcrumbs: None,
+            filter: None,
+            remap: None,
+            redactor: None,
+            symbolication: None,
+            storage: Storage::new(),
+        }
+    }
+
+    /// Override the [`PanicStyle`]
+    ///
+    /// By default, this is detected with [`PanicStyle::default`].
+    pub fn style(mut self, style: PanicStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Whether to write the report to disk
+    ///
+    /// When not persisted, the report is written to stderr instead.
+    ///
+    /// By default, reports are persisted unless running in CI.
+    pub fn persist(mut self, yes: bool) -> Self {
+        self.persist = Some(yes);
+        self
+    }
+
+    /// Serialization format for the report
+    ///
+    /// By default, this is [`Format::Toml`].
+    pub fn format(mut self, format: Format) -> Self {
+        self.storage = self.storage.format(format);
+        self
+    }
+
+    /// Where to write the report
+    ///
+    /// By default, this is [`Directory::Temp`], which may be cleared on reboot.  Prefer
+    /// [`Directory::State`] to keep reports until users send them.
+    pub fn directory(mut self, directory: Directory) -> Self {
+        self.storage = self.storage.directory(directory);
+        self
+    }
+
+    /// Limits on the reports kept in the directory
+    ///
+    /// By default, every report is kept.
+    pub fn retention(mut self, retention: Retention) -> Self {
+        self.storage = self.storage.retention(retention);
+        self
+    }
+
+    /// Combine reports for the same failure, see [`Storage::deduplicate`]
+    ///
+    /// By default, every failure gets its own report.
+    pub fn deduplicate(mut self, yes: bool) -> Self {
+        self.storage = self.storage.deduplicate(yes);
+        self
+    }
+
+    /// Hide uninteresting frames of reports' backtraces
+    ///
+    /// By default, the full backtrace is kept.
+    pub fn filter(mut self, filter: FrameFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Shorten the file paths in reports' backtraces
+    ///
+    /// By default, paths are left as-is.
+    pub fn remap(mut self, remap: Remap) -> Self {
+        let root = self.meta.workspace_root.as_deref();
+        self.remap = Some(remap.default_workspace_root(root));
+        self
+    }
+
+    /// Remove sensitive information from reports before they are written
+    ///
+    /// By default, reports are not redacted.
+    pub fn redact(mut self, redactor: Redactor) -> Self {
+        self.redactor = Some(redactor);
+        self
+    }
+
+    /// When to resolve the symbols of a panic's backtrace
+    ///
+    /// By default, this is [`Symbolication::Eager`].
+    pub fn symbolication(mut self, symbolication: Symbolication) -> Self {
+        self.symbolication = Some(symbolication);
+        self
+    }
+
+    /// Override the message shown to the user
+    ///
+    /// The callback receives the path to the report, if one was written.  See
+    /// [callbacks][PanicHandler#callbacks].
+    ///
+    /// By default, this is [`print_msg`].
+    pub fn message(
+        mut self,
+        message: impl Fn(Option<&Path>, &Metadata) -> IoResult<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.message = Some(Arc::new(message));
+        self
+    }
+
+    /// Keep the previously registered panic hook, running it in the given order
+    ///
+    /// By default, the previous hook is replaced.
+    pub fn chain(mut self, order: HookOrder) -> Self {
+        self.chain = Some(order);
+        self
+    }
+
+    /// How many [`breadcrumb`][crate::breadcrumb]s to keep for the report
+    ///
+    /// By default, this is 32.
+    pub fn breadcrumbs(mut self, count: usize) -> Self {
+        self.breadcrumbs = Some(count);
+        self
+    }
+
+    /// Register the panic hook
+    ///
+    /// Does nothing for [`PanicStyle::Debug`], leaving the current hook in place.
+    pub fn install(self) {
+        let _ = INSTALLED_AT.set(Instant::now());
+        if let Some(count) = self.breadcrumbs {
+            crate::breadcrumb::set_capacity(count);
+        }
+        match self.style.unwrap_or_default() {
+            PanicStyle::Debug => {}
+            PanicStyle::Human => {
+                let previous: Option<(HookOrder, PreviousHook)> =
+                    self.chain.map(|order| (order, panic::take_hook()));
+                panic::set_hook(Box::new(move |info: &PanicHookInfo<'_>| {
+                    if let Some((HookOrder::Before, previous)) = &previous {
+                        previous(info);
+                    }
+                    self.handle(info);
+                    if let Some((HookOrder::After, previous)) = &previous {
+                        previous(info);
+                    }
+                }));
+            }
+        }
+    }
+
+    /// Install the hook and run `main`, reporting any error it returns
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// use std::process::ExitCode;
+    ///
+    /// use human_panic::PanicHandler;
+    /// use human_panic::metadata;
+    ///
+    /// fn main() -> ExitCode {
+    ///     PanicHandler::new(metadata!()).run(|| {
+    ///         let config = std::fs::read_to_string("config.toml")?;
+    ///         println!("{config}");
+    ///         Ok::<_, std::io::Error>(())
+    ///     })
+    /// }
+    /// ```
+    pub fn run<E: Error>(self, main: impl FnOnce() -> Result<(), E>) -> ExitCode {
+        self.clone().install();
+        match main() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                self.report_error(&error);
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    /// Report an error returned from `main`
+    ///
+    /// For [`PanicStyle::Human`], this is reported like a panic.  For [`PanicStyle::Debug`], this
+    /// prints the error like returning it from `main` would.
+    pub fn report_error(&self, error: &dyn Error) {
+        self.report_with(error, || Report::from_error(&self.meta, error));
+    }
+
+    /// Report an [`anyhow::Error`] returned from `main`
+    ///
+    /// See [`PanicHandler::report_error`]
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// use std::process::ExitCode;
+    ///
+    /// use human_panic::PanicHandler;
+    /// use human_panic::metadata;
+    ///
+    /// fn main() -> ExitCode {
+    ///     let handler = PanicHandler::new(metadata!());
+    ///     handler.clone().install();
+    ///     match run() {
+    ///         Ok(()) => ExitCode::SUCCESS,
+    ///         Err(error) => {
+    ///             handler.report_anyhow(&error);
+    ///             ExitCode::FAILURE
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// fn run() -> anyhow::Result<()> {
+    ///     anyhow::bail!("not implemented")
+    /// }
+    /// ```
+    #[cfg(feature = "anyhow")]
+    pub fn report_anyhow(&self, error: &anyhow::Error) {
+        self.report_with(error, || Report::from_anyhow(&self.meta, error));
+    }
+
+    /// Report an [`eyre::Report`] returned from `main`
+    ///
+    /// See [`PanicHandler::report_error`]
+    #[cfg(feature = "eyre")]
+    pub fn report_eyre(&self, error: &eyre::Report) {
+        self.report_with(error, || Report::from_eyre(&self.meta, error));
+    }
 
-    #[allow(unused_imports)]
-    use std::panic;
+    fn report_with(&self, error: &dyn std::fmt::Debug, report: impl FnOnce() -> Report) {
+        match self.style.unwrap_or_default() {
+            PanicStyle::Debug => {
+                use std::io::Write as _;
+                let stderr = std::io::stderr();
+                let mut stderr = stderr.lock();
 
-    match PanicStyle::default() {
-        PanicStyle::Debug => {}
-        PanicStyle::Human => {
-            let meta = meta();
+                let _ = writeln!(stderr, "Error: {error:?}");
+            }
+            PanicStyle::Human => {
+                self.emit(report());
+            }
+        }
+    }
 
-            panic::set_hook(Box::new(move |info: &PanicHookInfo<'_>| {
-                let report = Report::with_panic(&meta, info);
-                let file_path = if is_ci() { None } else { report.persist().ok() };
-                if file_path.is_none() {
-                    use std::io::Write as _;
+    fn handle(&self, info: &PanicHookInfo<'_>) {
+        // Only the first of a burst of panics gets the full treatment; later ones, like from other
+        // threads or unwrapping a `join`, are summarized after it
+        let mut reported = REPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+        let burst = reported
+            .take()
+            .filter(|(last, _)| last.elapsed() < SECONDARY_PANIC_WINDOW);
+        let file_path = match burst {
+            None => {
+                let symbolication = self.symbolication.unwrap_or_default();
+                let report = Report::with_panic_symbolication(&self.meta, info, symbolication);
+                self.emit(report)
+            }
+            Some((_, file_path)) => {
+                use std::io::Write as _;
+
+                count_panic();
+                let summary = panic_summary(info);
+                {
                     let stderr = std::io::stderr();
                     let mut stderr = stderr.lock();
-
-                    let _ = writeln!(
-                        stderr,
-                        "{}",
-                        report
-                            .serialize()
-                            .expect("only doing toml compatible types")
-                    );
+                    let _ = writeln!(stderr, "{}: additional {summary}", self.meta.name);
                 }
-                print_msg(file_path.as_deref(), &meta)
-                    .expect("human-panic: printing error message to console failed");
-            }));
+                if let Some(file_path) = &file_path {
+                    let _ = self.storage.update(file_path, |report| {
+                        report.add_secondary_panic(summary);
+                        if let Some(redactor) = &self.redactor {
+                            report.redact(redactor);
+                        }
+                    });
+                }
+                file_path
+            }
+        };
+        *reported = Some((Instant::now(), file_path));
+    }
+
+    fn emit(&self, mut report: Report) -> Option<PathBuf> {
+        let persist = self.persist.unwrap_or_else(|| !is_ci());
+        if !report.is_resolved() {
+            return self.emit_deferred(report, persist);
         }
+
+        self.prepare(&mut report);
+        let file_path = if persist {
+            self.storage.persist(&report).ok()
+        } else {
+            None
+        };
+        if file_path.is_none() {
+            self.dump(&report);
+        }
+        self.print(file_path.as_deref(), report.cause());
+
+        file_path
+    }
+
+    /// Tell the user before resolving symbols, which can be slow, then write the full report
+    ///
+    /// The report is first written without symbols, so the file named to the user exists even if
+    /// resolving them never finishes.
+    fn emit_deferred(&self, mut report: Report, persist: bool) -> Option<PathBuf> {
+        let written = persist
+            .then(|| {
+                let file_path = self.storage.path_for(&report);
+                let mut partial = report.clone();
+                self.prepare(&mut partial);
+                let fresh = self.storage.write(&file_path, &partial).ok()?;
+                Some((file_path, fresh))
+            })
+            .flatten();
+        self.print(
+            written.as_ref().map(|(path, _)| path.as_path()),
+            report.cause(),
+        );
+        if let Some((file_path, false)) = written {
+            // An earlier, already resolved, report was updated instead
+            return Some(file_path);
+        }
+
+        report.resolve();
+        report.add_frame_snippets(&self.meta);
+        self.prepare(&mut report);
+        let file_path = written.map(|(file_path, _)| file_path);
+        let replaced = file_path
+            .as_deref()
+            .is_some_and(|file_path| self.storage.replace(file_path, &report).is_ok());
+        if !replaced {
+            self.dump(&report);
+        }
+
+        file_path
     }
+
+    fn prepare(&self, report: &mut Report) {
+        if let Some(filter) = &self.filter {
+            report.filter_backtrace(filter);
+        }
+        if let Some(remap) = &self.remap {
+            report.remap(remap);
+        }
+        if let Some(redactor) = &self.redactor {
+            report.redact(redactor);
+        }
+    }
+
+    /// Write `report` to stderr, for when it isn't persisted
+    fn dump(&self, report: &Report) {
+        if let Some(serialized) = report.serialize_as(self.storage.format) {
+            use std::io::Write as _;
+            let stderr = std::io::stderr();
+            let mut stderr = stderr.lock();
+
+            let _ = writeln!(stderr, "{serialized}");
+        }
+    }
+
+    fn print(&self, file_path: Option<&Path>, cause: &str) {
+        let printed = match &self.message {
+            Some(message) => message(file_path, &self.meta),
+            None => print_msg(file_path, &self.meta),
+        };
+        if printed.is_err() {
+            write_minimal(&self.meta.name, cause);
+        }
+    }
+}
+
+/// When the first [`PanicHandler`] was installed, as a proxy for process start
+static INSTALLED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// How long since the first [`PanicHandler`] was installed
+pub(crate) fn uptime() -> Option<Duration> {
+    INSTALLED_AT.get().map(Instant::elapsed)
+}
+
+/// When the hook last handled a panic, and the file the current burst was written to
+static REPORTED: Mutex<Option<(Instant, Option<PathBuf>)>> = Mutex::new(None);
+
+/// How soon after the previous panic another is summarized as part of the same burst
+const SECONDARY_PANIC_WINDOW: Duration = Duration::from_secs(1);
+
+/// Tell the user about a failure without allocating
+///
+/// This is the fallback for when the full message can't be shown, like from a closed stderr.
+fn write_minimal(name: &str, cause: &str) {
+    use std::io::Write as _;
+    let mut stderr = std::io::stderr();
+
+    let _ = stderr.write_all(name.as_bytes());
+    let _ = stderr.write_all(b" had a problem and crashed: ");
+    let _ = stderr.write_all(cause.as_bytes());
+    let _ = stderr.write_all(b"\n");
 }
 
 /// Returns whether we are running in a CI environment.
@@ -45,6 +472,40 @@ fn is_ci() -> bool {
     std::env::var_os("CI").is_some()
 }
 
+/// When to run the previous panic hook, relative to `human-panic`
+///
+/// See [`PanicHandler::chain`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HookOrder {
+    /// Run the previous hook before reporting
+    Before,
+    /// Run the previous hook after reporting
+    After,
+}
+
+/// When to resolve the symbols of a panic's backtrace
+///
+/// Resolving symbols reads the binary's debug information, which can be slow for large binaries
+/// and can itself fail.
+///
+/// See [`PanicHandler::symbolication`]
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Symbolication {
+    /// Resolve symbols while capturing the backtrace
+    #[default]
+    Eager,
+    /// Show the message first, then resolve symbols and write the report
+    ///
+    /// [`Report::fingerprint`] only covers the panic's message and location.
+    Deferred,
+    /// Never resolve symbols, recording each frame's [`Frame::ip`][crate::report::Frame::ip] and
+    /// [`Frame::module_base`][crate::report::Frame::module_base] to symbolicate offline
+    ///
+    /// [`Report::fingerprint`] only covers the panic's message and location.
+    Offline,
+}
+
 /// Style of panic to be used
 #[non_exhaustive]
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -157,13 +618,9 @@ pub fn handle_dump(meta: &Metadata, panic_info: &PanicHookInfo<'_>) -> Option<Pa
         let stderr = std::io::stderr();
         let mut stderr = stderr.lock();
 
-        let _ = writeln!(
-            stderr,
-            "{}",
-            report
-                .serialize()
-                .expect("only doing toml compatible types")
-        );
+        if let Some(serialized) = report.serialize() {
+            let _ = writeln!(stderr, "{serialized}");
+        }
         None
     }
 }
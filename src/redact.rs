@@ -0,0 +1,206 @@
+use std::env;
+use std::sync::Arc;
+
+type RedactFn = dyn Fn(&str) -> Option<String> + Send + Sync + 'static;
+
+/// Removes sensitive information from a [`Report`][crate::report::Report] before it is written
+///
+/// By default, this replaces
+/// - the home directory with `~`
+/// - the username with `<user>`
+/// - the hostname with `<host>`
+///
+/// The names of the rules that changed anything are recorded in
+/// [`Report::redactions`][crate::report::Report::redactions].
+///
+/// ## Example
+///
+/// ```rust
+/// use human_panic::report::Redactor;
+///
+/// let redactor = Redactor::new()
+///     .hostname(false)
+///     .callback("api-key", |text| {
+///         let key = std::env::var("MY_APP_API_KEY").ok().filter(|key| !key.is_empty())?;
+///         text.contains(&key).then(|| text.replace(&key, "<api-key>"))
+///     });
+/// ```
+#[derive(Clone)]
+pub struct Redactor {
+    home: bool,
+    username: bool,
+    hostname: bool,
+    rules: Vec<(String, Rule)>,
+}
+
+#[derive(Clone)]
+enum Rule {
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex, String),
+    Callback(Arc<RedactFn>),
+}
+
+impl Redactor {
+    /// Redact the home directory, username, and hostname
+    pub fn new() -> Self {
+        Self {
+            home: true,
+            username: true,
+            hostname: true,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Replace the home directory with `~`, recorded as `home`
+    pub fn home(mut self, yes: bool) -> Self {
+        self.home = yes;
+        self
+    }
+
+    /// Replace the username with `<user>`, recorded as `username`
+    pub fn username(mut self, yes: bool) -> Self {
+        self.username = yes;
+        self
+    }
+
+    /// Replace the hostname with `<host>`, recorded as `hostname`
+    pub fn hostname(mut self, yes: bool) -> Self {
+        self.hostname = yes;
+        self
+    }
+
+    /// Replace matches of `regex`, recorded as `name`
+    ///
+    /// `replacement` may refer to capture groups, see [`regex::Regex::replace_all`].
+    #[cfg(feature = "regex")]
+    pub fn regex(
+        mut self,
+        name: impl Into<String>,
+        regex: regex::Regex,
+        replacement: impl Into<String>,
+    ) -> Self {
+        self.rules
+            .push((name.into(), Rule::Regex(regex, replacement.into())));
+        self
+    }
+
+    /// Rewrite text with `redact`, recorded as `name`
+    ///
+    /// `redact` returns `None` to leave the text unchanged.  See
+    /// [callbacks][crate::PanicHandler#callbacks].
+    pub fn callback(
+        mut self,
+        name: impl Into<String>,
+        redact: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.rules
+            .push((name.into(), Rule::Callback(Arc::new(redact))));
+        self
+    }
+
+    /// Redact each of `texts`, returning the names of the rules that changed anything
+    pub(crate) fn redact<'t>(
+        &self,
+        texts: impl IntoIterator<Item = &'t mut String>,
+    ) -> Vec<String> {
+        // Home first, as it usually contains the username
+        let mut literals = Vec::new();
+        if self.home
+            && let Some(home) =
+                env::home_dir().and_then(|home| home.to_str().map(ToOwned::to_owned))
+            && 1 < home.len()
+        {
+            literals.push(("home", home, "~", false));
+        }
+        if self.username
+            && let Some(username) = env::var("USER")
+                .or_else(|_| env::var("USERNAME"))
+                .ok()
+                .filter(|username| !username.is_empty())
+        {
+            literals.push(("username", username, "<user>", true));
+        }
+        if self.hostname
+            && let Some(hostname) =
+                sysinfo::System::host_name().filter(|hostname| !hostname.is_empty())
+        {
+            literals.push(("hostname", hostname, "<host>", true));
+        }
+
+        let mut applied = vec![false; literals.len() + self.rules.len()];
+        for text in texts {
+            for (i, (_, needle, replacement, whole_word)) in literals.iter().enumerate() {
+                let redacted = if *whole_word {
+                    replace_word(text, needle, replacement)
+                } else {
+                    text.contains(needle.as_str())
+                        .then(|| text.replace(needle.as_str(), replacement))
+                };
+                if let Some(redacted) = redacted {
+                    *text = redacted;
+                    applied[i] = true;
+                }
+            }
+            for (i, (_, rule)) in self.rules.iter().enumerate() {
+                if let Some(redacted) = rule.redact(text) {
+                    *text = redacted;
+                    applied[literals.len() + i] = true;
+                }
+            }
+        }
+
+        let names = literals
+            .iter()
+            .map(|(name, ..)| *name)
+            .chain(self.rules.iter().map(|(name, _)| name.as_str()));
+        names
+            .zip(applied)
+            .filter(|(_, applied)| *applied)
+            .map(|(name, _)| name.to_owned())
+            .collect()
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule {
+    fn redact(&self, text: &str) -> Option<String> {
+        match self {
+            #[cfg(feature = "regex")]
+            Self::Regex(regex, replacement) => {
+                match regex.replace_all(text, replacement.as_str()) {
+                    std::borrow::Cow::Borrowed(_) => None,
+                    std::borrow::Cow::Owned(redacted) => Some(redacted),
+                }
+            }
+            Self::Callback(redact) => redact(text),
+        }
+    }
+}
+
+/// Replace `word` in `text` where it isn't part of a larger word
+fn replace_word(text: &str, word: &str, replacement: &str) -> Option<String> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut redacted = String::new();
+    let mut last = 0;
+    for (start, _) in text.match_indices(word) {
+        let end = start + word.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        if before.is_some_and(is_word_char) || after.is_some_and(is_word_char) {
+            continue;
+        }
+        redacted.push_str(&text[last..start]);
+        redacted.push_str(replacement);
+        last = end;
+    }
+    if last == 0 {
+        return None;
+    }
+    redacted.push_str(&text[last..]);
+    Some(redacted)
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+/// Shortens the file paths in a [`Report`][crate::report::Report]'s backtrace
+///
+/// By default, this rewrites
+/// - registry sources, like `~/.cargo/registry/src/index.crates.io-<hash>/serde-1.0.228/src/de.rs`,
+///   to `serde-1.0.228/src/de.rs`
+/// - Rust sources, like `/rustc/<commit>/library/std/src/panicking.rs`, to
+///   `<rust>/library/std/src/panicking.rs`
+/// - paths within the workspace to be relative to it, when the workspace root is known from
+///   [`build::emit`][crate::build::emit]
+///
+/// ## Example
+///
+/// ```rust
+/// use human_panic::report::Remap;
+///
+/// let remap = Remap::new()
+///     .prefix("/opt/vendor/", "<vendor>/");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remap {
+    registry: bool,
+    rustc: bool,
+    workspace: bool,
+    workspace_root: Option<PathBuf>,
+    prefixes: Vec<(String, String)>,
+}
+
+impl Remap {
+    /// Rewrite registry, Rust, and workspace paths
+    pub fn new() -> Self {
+        Self {
+            registry: true,
+            rustc: true,
+            workspace: true,
+            workspace_root: None,
+            prefixes: Vec::new(),
+        }
+    }
+
+    /// Rewrite registry sources to `<crate>-<version>/...`
+    pub fn registry(mut self, yes: bool) -> Self {
+        self.registry = yes;
+        self
+    }
+
+    /// Rewrite Rust sources to `<rust>/library/...`
+    pub fn rustc(mut self, yes: bool) -> Self {
+        self.rustc = yes;
+        self
+    }
+
+    /// Make paths within the workspace relative to it
+    pub fn workspace(mut self, yes: bool) -> Self {
+        self.workspace = yes;
+        self
+    }
+
+    /// Override the workspace root
+    ///
+    /// By default, this is from [`Metadata::workspace_root`][crate::Metadata::workspace_root].
+    pub fn workspace_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.workspace_root = Some(root.into());
+        self
+    }
+
+    /// Replace the `from` prefix of paths with `to`
+    ///
+    /// These are checked in order, before the built-in rules.
+    pub fn prefix(mut self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.prefixes.push((from.into(), to.into()));
+        self
+    }
+
+    pub(crate) fn default_workspace_root(mut self, root: Option<&str>) -> Self {
+        if self.workspace_root.is_none() {
+            self.workspace_root = root.map(PathBuf::from);
+        }
+        self
+    }
+
+    /// The rewritten `path`, if any rule applies
+    pub(crate) fn remap(&self, path: &str) -> Option<String> {
+        for (from, to) in &self.prefixes {
+            if let Some(rest) = path.strip_prefix(from.as_str()) {
+                return Some(format!("{to}{rest}"));
+            }
+        }
+        let components = path.split(['/', '\\']).collect::<Vec<_>>();
+        if self.rustc
+            && let Some(rest) = rustc_source(&components)
+        {
+            return Some(format!("<rust>/{}", rest.join("/")));
+        }
+        if self.registry
+            && let Some(rest) = registry_source(&components)
+        {
+            return Some(rest.join("/"));
+        }
+        if self.workspace
+            && let Some(root) = &self.workspace_root
+            && let Ok(relative) = Path::new(path).strip_prefix(root)
+        {
+            return relative.to_str().map(ToOwned::to_owned);
+        }
+        None
+    }
+}
+
+impl Default for Remap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `path` is in the Rust sources or a crate from the registry
+pub(crate) fn is_dependency(path: &str) -> bool {
+    let components = path.split(['/', '\\']).collect::<Vec<_>>();
+    rustc_source(&components).is_some() || registry_source(&components).is_some()
+}
+
+/// The path within the Rust sources, from `/rustc/<commit>/...` or a toolchain's `rust-src`
+fn rustc_source<'p>(components: &'p [&'p str]) -> Option<&'p [&'p str]> {
+    let start = if let ["", "rustc", _commit, ..] = components {
+        3
+    } else {
+        components
+            .windows(4)
+            .position(|w| w == ["lib", "rustlib", "src", "rust"])?
+            + 4
+    };
+    components.get(start..).filter(|rest| !rest.is_empty())
+}
+
+/// The path within a crate from the registry, starting with `<crate>-<version>`
+fn registry_source<'p>(components: &'p [&'p str]) -> Option<&'p [&'p str]> {
+    // Skip the `<index>-<hash>` directory
+    let start = components
+        .windows(2)
+        .position(|w| w == ["registry", "src"])?
+        + 3;
+    components.get(start..).filter(|rest| !rest.is_empty())
+}
@@ -3,39 +3,320 @@
 //! A `Report` contains the metadata collected about the event
 //! to construct a helpful error message.
 
+use std::alloc::Layout;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::fmt::Write as FmtWrite;
 use std::mem;
 use std::panic::PanicHookInfo;
-use std::{env, path::Path, path::PathBuf};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::ThreadId;
 
 use backtrace::Backtrace;
-use serde_derive::Serialize;
-use uuid::Uuid;
+use serde_derive::{Deserialize, Serialize};
 
 use crate::Metadata;
+use crate::build::Build;
+use crate::module::Modules;
+use crate::panic::Symbolication;
+
+pub use crate::filter::FrameFilter;
+pub use crate::redact::Redactor;
+pub use crate::remap::Remap;
+pub use crate::storage::{Directory, Retention, Storage};
 
 /// Method of failure.
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Method {
     /// Failure caused by a panic.
     Panic,
+    /// Failure caused by an error returned from `main`.
+    Error,
+    /// Failure caused by a fatal signal.
+    Signal,
+    /// Failure caused by aborting the process.
+    Abort,
+    /// Failure caused by being unable to allocate memory.
+    OutOfMemory,
+}
+
+/// Serialization format of a [`Report`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// [TOML](https://toml.io)
+    #[default]
+    Toml,
+    /// [JSON](https://www.json.org)
+    Json,
+}
+
+impl Format {
+    /// File extension used when persisting a report in this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Toml => "toml",
+            Self::Json => "json",
+        }
+    }
+
+    pub(crate) fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
 }
 
 /// Contains metadata about the crash like the backtrace and
 /// information about the crate and operating system. Can
 /// be used to be serialized and persisted or printed as
 /// information to the user.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Report {
     name: String,
     operating_system: String,
     crate_version: String,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    uptime_ms: Option<u64>,
+    #[serde(default)]
+    pid: u32,
     explanation: String,
     cause: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    causes: Vec<String>,
     method: Method,
-    backtrace: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thread_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thread_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_panics: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    secondary_panics: Vec<String>,
+    fingerprint: String,
+    #[serde(default = "default_occurrences")]
+    occurrences: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_seen: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    redactions: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    custom: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Build::is_empty")]
+    build: Build,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    snippets: Vec<Snippet>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    breadcrumbs: Vec<Breadcrumb>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    logs: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    spans: Vec<Span>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<String>,
+    backtrace: Vec<Frame>,
+    /// Captured with [`Symbolication::Deferred`], until [`Report::resolve`]
+    #[serde(skip)]
+    unresolved: Option<Backtrace>,
+}
+
+fn default_occurrences() -> u64 {
+    1
+}
+
+/// Source code around a line of a [`Report`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snippet {
+    file: String,
+    line: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    column: Option<u32>,
+    source: String,
+}
+
+impl Snippet {
+    pub(crate) fn new(file: String, line: u32, column: Option<u32>, source: String) -> Self {
+        Self {
+            file,
+            line,
+            column,
+            source,
+        }
+    }
+
+    /// Source file, as recorded in the backtrace
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Line of interest
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Column of interest, if known
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+
+    /// The lines around [`Snippet::line`], numbered, with [`Snippet::column`] marked
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// An event recorded with [`breadcrumb`][crate::breadcrumb] before the failure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breadcrumb {
+    timestamp: String,
+    message: String,
+}
+
+impl Breadcrumb {
+    pub(crate) fn new(timestamp: String, message: String) -> Self {
+        Self { timestamp, message }
+    }
+
+    /// When the event was recorded, as an RFC 3339 UTC timestamp
+    pub fn timestamp(&self) -> &str {
+        &self.timestamp
+    }
+
+    /// Description of the event
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// A `tracing` span that was entered when the failure occurred
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    name: String,
+    target: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    fields: BTreeMap<String, String>,
+}
+
+impl Span {
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) fn new(name: String, target: String, fields: BTreeMap<String, String>) -> Self {
+        Self {
+            name,
+            target,
+            fields,
+        }
+    }
+
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub(crate) fn set_fields(&mut self, fields: BTreeMap<String, String>) {
+        self.fields = fields;
+    }
+
+    /// Name of the span
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Module path, or other target, the span was created in
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Values of the span's fields, formatted with [`Debug`][std::fmt::Debug]
+    ///
+    /// String values are recorded as-is.
+    pub fn fields(&self) -> &BTreeMap<String, String> {
+        &self.fields
+    }
+}
+
+/// A single entry of a [`Report`]'s backtrace
+///
+/// Inlined functions get their own entry, sharing the instruction pointer of the frame they were
+/// inlined into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frame {
+    index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    module_base: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    module: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    column: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    inlined: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    hidden: bool,
+}
+
+impl Frame {
+    /// Position within the backtrace, starting from the failure
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Instruction pointer, formatted as hex, if known
+    pub fn ip(&self) -> Option<&str> {
+        self.ip.as_deref()
+    }
+
+    /// Address the module containing [`Frame::ip`] was loaded at, formatted as hex, if unresolved
+    ///
+    /// With [`Frame::module`], this lets the frame be symbolicated offline, see
+    /// [`Symbolication::Offline`].
+    pub fn module_base(&self) -> Option<&str> {
+        self.module_base.as_deref()
+    }
+
+    /// Path of the binary or shared library containing [`Frame::ip`], if unresolved
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Demangled symbol name, if resolved
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Source file, if debug information is available
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Source line, if debug information is available
+    pub fn line(&self) -> Option<u32> {
+        self.line
+    }
+
+    /// Source column, if debug information is available
+    pub fn column(&self) -> Option<u32> {
+        self.column
+    }
+
+    /// Whether this function was inlined into the next entry
+    pub fn is_inlined(&self) -> bool {
+        self.inlined
+    }
+
+    /// Whether this entry was hidden by a [`FrameFilter`] and is left out of
+    /// [`Report::render_backtrace`]
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
 }
 
 impl Report {
@@ -46,41 +327,76 @@ impl Report {
         method: Method,
         explanation: String,
         cause: String,
+    ) -> Self {
+        Self::with_backtrace(
+            name,
+            version,
+            method,
+            explanation,
+            cause,
+            capture_backtrace(),
+        )
+    }
+
+    fn with_backtrace(
+        name: &str,
+        version: &str,
+        method: Method,
+        explanation: String,
+        cause: String,
+        backtrace: Vec<Frame>,
     ) -> Self {
         let cpu_arch = sysinfo::System::cpu_arch();
         let operating_system =
             sysinfo::System::long_os_version().unwrap_or_else(|| "unknown".to_owned());
         let operating_system = format!("{operating_system} [{cpu_arch}]");
-        let backtrace = render_backtrace();
+        let fingerprint = fingerprint(name, &explanation, &cause, &backtrace);
+        let thread = std::thread::current();
 
         Self {
             crate_version: version.into(),
             name: name.into(),
             operating_system,
+            timestamp: crate::time::now(),
+            uptime_ms: crate::panic::uptime().map(|uptime| uptime.as_millis() as u64),
+            pid: std::process::id(),
             method,
             explanation,
             cause,
+            causes: Vec::new(),
+            thread_name: thread.name().map(ToOwned::to_owned),
+            thread_id: thread_id(thread.id()),
+            previous_panics: None,
+            secondary_panics: Vec::new(),
+            fingerprint,
+            occurrences: 1,
+            last_seen: None,
+            redactions: Vec::new(),
+            custom: BTreeMap::new(),
+            build: Build::default(),
+            snippets: Vec::new(),
+            breadcrumbs: Vec::new(),
+            logs: Vec::new(),
+            spans: Vec::new(),
+            events: Vec::new(),
             backtrace,
+            unresolved: None,
         }
     }
 
     #[allow(deprecated)]
     pub fn with_panic(meta: &Metadata, panic_info: &PanicHookInfo<'_>) -> Self {
-        let mut expl = String::new();
+        Self::with_panic_symbolication(meta, panic_info, Symbolication::Eager)
+    }
 
-        let message = match (
-            panic_info.payload().downcast_ref::<&str>(),
-            panic_info.payload().downcast_ref::<String>(),
-        ) {
-            (Some(s), _) => Some((*s).to_owned()),
-            (_, Some(s)) => Some(s.to_owned()),
-            (None, None) => None,
-        };
+    pub(crate) fn with_panic_symbolication(
+        meta: &Metadata,
+        panic_info: &PanicHookInfo<'_>,
+        symbolication: Symbolication,
+    ) -> Self {
+        let mut expl = String::new();
 
-        let cause = match message {
-            Some(m) => m,
-            None => "Unknown".into(),
-        };
+        let cause = panic_message(panic_info).unwrap_or("Unknown").to_owned();
 
         match panic_info.location() {
             Some(location) => expl.push_str(&format!(
@@ -91,85 +407,778 @@ impl Report {
             None => expl.push_str("Panic location unknown.\n"),
         }
 
-        Self::new(&meta.name, &meta.version, Method::Panic, expl, cause)
+        let (backtrace, unresolved) = match symbolication {
+            Symbolication::Eager => (capture_backtrace(), None),
+            Symbolication::Deferred | Symbolication::Offline => {
+                let backtrace = Backtrace::new_unresolved();
+                let frames = trim_to_panic(backtrace_frames(&backtrace));
+                let deferred = symbolication == Symbolication::Deferred;
+                (frames, deferred.then_some(backtrace))
+            }
+        };
+        let mut report = Self::with_metadata(meta, Method::Panic, expl, cause, backtrace);
+        report.unresolved = unresolved;
+        report.previous_panics = Some(count_panic());
+        if let Some(location) = panic_info.location()
+            && let Some(snippet) = crate::snippet::location_snippet(
+                meta,
+                location.file(),
+                location.line(),
+                Some(location.column()),
+            )
+        {
+            report
+                .snippets
+                .retain(|s| s.file() != snippet.file() || s.line() != snippet.line());
+            report.snippets.insert(0, snippet);
+        }
+        report
+    }
+
+    /// Report an error returned from `main`, including its [`Error::source`]s
+    pub fn from_error(meta: &Metadata, error: &dyn Error) -> Self {
+        Self::from_error_with_backtrace(meta, error, capture_backtrace())
+    }
+
+    /// Report an [`anyhow::Error`], including its context
+    ///
+    /// The error's own backtrace is used, if one was captured.
+    #[cfg(feature = "anyhow")]
+    pub fn from_anyhow(meta: &Metadata, error: &anyhow::Error) -> Self {
+        let backtrace = parse_std_backtrace(error.backtrace()).unwrap_or_else(capture_backtrace);
+        Self::from_error_with_backtrace(meta, error.as_ref(), backtrace)
+    }
+
+    /// Report an [`eyre::Report`], including its context
+    ///
+    /// The error's own backtrace is used, if one was captured by [`eyre::DefaultHandler`].
+    #[cfg(feature = "eyre")]
+    pub fn from_eyre(meta: &Metadata, error: &eyre::Report) -> Self {
+        let debug = format!("{error:?}");
+        let backtrace = debug
+            .split_once("\n\nStack backtrace:\n")
+            .and_then(|(_, backtrace)| parse_std_backtrace_str(backtrace))
+            .unwrap_or_else(capture_backtrace);
+        Self::from_error_with_backtrace(meta, error.as_ref(), backtrace)
+    }
+
+    fn from_error_with_backtrace(
+        meta: &Metadata,
+        error: &dyn Error,
+        backtrace: Vec<Frame>,
+    ) -> Self {
+        let expl = "Error returned from `main`\n".to_owned();
+        let mut report =
+            Self::with_metadata(meta, Method::Error, expl, error.to_string(), backtrace);
+        report.causes = std::iter::successors(error.source(), |&e| e.source())
+            .map(|e| e.to_string())
+            .collect();
+        report
+    }
+
+    /// Report a fatal signal
+    ///
+    /// Capturing a report is not async-signal-safe, so this is best effort when called from a
+    /// signal handler.
+    pub fn with_signal(meta: &Metadata, signal: i32) -> Self {
+        let cause = match signal_name(signal) {
+            Some(name) => name.to_owned(),
+            None => format!("signal {signal}"),
+        };
+        let expl = format!("Process received signal {signal}\n");
+        Self::with_metadata(meta, Method::Signal, expl, cause, capture_backtrace())
+    }
+
+    /// Report the process aborting, for example from [`std::process::abort`]
+    pub fn with_abort(meta: &Metadata, reason: &str) -> Self {
+        let expl = "Process aborted\n".to_owned();
+        Self::with_metadata(
+            meta,
+            Method::Abort,
+            expl,
+            reason.to_owned(),
+            capture_backtrace(),
+        )
+    }
+
+    /// Report a failure to allocate memory, for example from an allocation error hook
+    pub fn with_alloc_error(meta: &Metadata, layout: Layout) -> Self {
+        let expl = "Memory allocation failed\n".to_owned();
+        let cause = format!("memory allocation of {} bytes failed", layout.size());
+        Self::with_metadata(meta, Method::OutOfMemory, expl, cause, capture_backtrace())
+    }
+
+    fn with_metadata(
+        meta: &Metadata,
+        method: Method,
+        explanation: String,
+        cause: String,
+        backtrace: Vec<Frame>,
+    ) -> Self {
+        let mut report = Self::with_backtrace(
+            &meta.name,
+            &meta.version,
+            method,
+            explanation,
+            cause,
+            backtrace,
+        );
+        report.custom = meta.custom();
+        report.build = meta.build.clone();
+        report.snippets = crate::snippet::frame_snippets(meta, &report.backtrace);
+        report.breadcrumbs = crate::breadcrumb::trail();
+        #[cfg(feature = "log")]
+        {
+            report.logs = crate::log::records();
+        }
+        #[cfg(feature = "tracing")]
+        {
+            report.spans = crate::tracing::spans();
+            report.events = crate::tracing::events();
+        }
+        report
+    }
+
+    /// Name of the crate that failed
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Operating system and CPU architecture the failure occurred on
+    pub fn operating_system(&self) -> &str {
+        &self.operating_system
+    }
+
+    /// Version of the crate that failed
+    pub fn crate_version(&self) -> &str {
+        &self.crate_version
+    }
+
+    /// When the failure occurred, as an [RFC 3339](https://www.rfc-editor.org/rfc/rfc3339) UTC
+    /// timestamp
+    pub fn timestamp(&self) -> &str {
+        &self.timestamp
+    }
+
+    /// How long the process ran before failing, in milliseconds
+    ///
+    /// This is measured from when the [`PanicHandler`][crate::PanicHandler] was installed.
+    pub fn uptime_ms(&self) -> Option<u64> {
+        self.uptime_ms
+    }
+
+    /// Id of the process that failed
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Description of where the failure occurred
+    pub fn explanation(&self) -> &str {
+        &self.explanation
+    }
+
+    /// Message of the failure
+    pub fn cause(&self) -> &str {
+        &self.cause
+    }
+
+    /// Underlying causes of the failure, from [`Error::source`]
+    pub fn causes(&self) -> &[String] {
+        &self.causes
+    }
+
+    /// How the failure happened
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Name of the thread the failure occurred on, if it has one
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    /// Numeric id of the thread the failure occurred on
+    ///
+    /// See [`std::thread::ThreadId`]
+    pub fn thread_id(&self) -> Option<u64> {
+        self.thread_id
+    }
+
+    /// For panics, how many panics were reported earlier in the process
+    ///
+    /// A non-zero count means this is a follow-on panic, like from unwrapping a failed
+    /// [`std::thread::JoinHandle::join`].
+    pub fn previous_panics(&self) -> Option<usize> {
+        self.previous_panics
+    }
+
+    /// Summaries of panics that happened after this one was reported
+    pub fn secondary_panics(&self) -> &[String] {
+        &self.secondary_panics
+    }
+
+    pub(crate) fn add_secondary_panic(&mut self, summary: String) {
+        self.secondary_panics.push(summary);
+    }
+
+    /// Stable identifier for failures of the same application with the same location, cause, and
+    /// call stack
+    ///
+    /// Numbers in the cause are ignored, so `index 5 out of bounds` and `index 7 out of bounds`
+    /// from the same place share a fingerprint.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// How many times this failure has been seen, see [`Storage::deduplicate`]
+    pub fn occurrences(&self) -> u64 {
+        self.occurrences
+    }
+
+    /// When this failure was last seen, if it happened more than once
+    pub fn last_seen(&self) -> Option<&str> {
+        self.last_seen.as_deref()
+    }
+
+    /// Record another occurrence of this failure
+    pub(crate) fn recur(&mut self) {
+        self.occurrences += 1;
+        self.last_seen = Some(crate::time::now());
+    }
+
+    /// Names of the [`Redactor`] rules that changed this report
+    pub fn redactions(&self) -> &[String] {
+        &self.redactions
+    }
+
+    /// Remove sensitive information, like the user's home directory, from the report
+    ///
+    /// The explanation, causes, thread name, custom fields, recorded context, and backtrace
+    /// symbols, files, and modules are redacted.
+    pub fn redact(&mut self, redactor: &Redactor) {
+        let mut texts = vec![&mut self.explanation, &mut self.cause];
+        texts.extend(&mut self.causes);
+        texts.extend(&mut self.thread_name);
+        texts.extend(&mut self.secondary_panics);
+        texts.extend(self.custom.values_mut());
+        texts.extend(self.breadcrumbs.iter_mut().map(|b| &mut b.message));
+        texts.extend(&mut self.logs);
+        texts.extend(self.spans.iter_mut().flat_map(|s| s.fields.values_mut()));
+        texts.extend(&mut self.events);
+        for frame in &mut self.backtrace {
+            texts.extend(&mut frame.name);
+            texts.extend(&mut frame.file);
+            texts.extend(&mut frame.module);
+        }
+        for snippet in &mut self.snippets {
+            texts.push(&mut snippet.file);
+            texts.push(&mut snippet.source);
+        }
+        for name in redactor.redact(texts) {
+            if !self.redactions.contains(&name) {
+                self.redactions.push(name);
+            }
+        }
+    }
+
+    /// Hide uninteresting frames of the backtrace
+    pub fn filter_backtrace(&mut self, filter: &FrameFilter) {
+        let hidden = filter.hidden(&self.backtrace);
+        if filter.is_keep_full() {
+            for (frame, hidden) in self.backtrace.iter_mut().zip(hidden) {
+                frame.hidden |= hidden;
+            }
+        } else {
+            let mut hidden = hidden.into_iter();
+            self.backtrace.retain(|_| !hidden.next().unwrap_or(false));
+        }
+    }
+
+    /// Shorten the file and module paths in the backtrace
+    pub fn remap(&mut self, remap: &Remap) {
+        for frame in &mut self.backtrace {
+            for path in [&mut frame.file, &mut frame.module] {
+                if let Some(remapped) = path.as_deref().and_then(|path| remap.remap(path)) {
+                    *path = Some(remapped);
+                }
+            }
+        }
+        for snippet in &mut self.snippets {
+            if let Some(remapped) = remap.remap(&snippet.file) {
+                snippet.file = remapped;
+            }
+        }
+    }
+
+    /// Application-specific fields, see [`Metadata::field`] and [`Metadata::fields`]
+    pub fn custom(&self) -> &BTreeMap<String, String> {
+        &self.custom
+    }
+
+    /// Provenance of the build that failed, see [`crate::build`]
+    pub fn build(&self) -> &Build {
+        &self.build
+    }
+
+    /// Source code around the failure and the innermost frames of the application
+    ///
+    /// These are only included when the source files could be read, like in development and CI.
+    /// Relative paths are looked up in [`Metadata::workspace_root`], or else in the workspace
+    /// containing [`Metadata::manifest_dir`], never in the current directory.
+    pub fn snippets(&self) -> &[Snippet] {
+        &self.snippets
+    }
+
+    /// Events recorded with [`breadcrumb`][crate::breadcrumb] before the failure, oldest first
+    pub fn breadcrumbs(&self) -> &[Breadcrumb] {
+        &self.breadcrumbs
+    }
+
+    /// Recent records captured by the `log` feature's `Logger`, oldest first
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// The `tracing` spans entered on the failing thread, outermost first
+    ///
+    /// See the `tracing` feature's `Layer`.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Recent events captured by the `tracing` feature's `Layer`, oldest first
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    /// Backtrace captured at the time of the failure
+    pub fn backtrace(&self) -> &[Frame] {
+        &self.backtrace
+    }
+
+    /// Whether [`Report::backtrace`] is still waiting on [`Report::resolve`]
+    pub fn is_resolved(&self) -> bool {
+        self.unresolved.is_none()
+    }
+
+    /// Resolve the symbols of a backtrace captured with [`Symbolication::Deferred`]
+    ///
+    /// [`Report::fingerprint`] is left as it was computed from the unresolved backtrace, so the
+    /// report still matches where it was written.
+    pub fn resolve(&mut self) {
+        let Some(mut backtrace) = self.unresolved.take() else {
+            return;
+        };
+        backtrace.resolve();
+        self.backtrace = trim_to_panic(backtrace_frames(&backtrace));
+    }
+
+    /// Add the snippets for the application frames of a newly resolved backtrace
+    pub(crate) fn add_frame_snippets(&mut self, meta: &Metadata) {
+        for snippet in crate::snippet::frame_snippets(meta, &self.backtrace) {
+            if !self
+                .snippets
+                .iter()
+                .any(|s| s.file() == snippet.file() && s.line() == snippet.line())
+            {
+                self.snippets.push(snippet);
+            }
+        }
+    }
+
+    /// Render [`Report::backtrace`] as human-readable text
+    pub fn render_backtrace(&self) -> String {
+        render_backtrace(&self.backtrace)
+    }
+
+    /// Read a report previously written with [`Report::persist`]
+    ///
+    /// The [`Format`] is detected from the file extension, falling back to the content.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error + 'static>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let format = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Format::from_extension)
+            .unwrap_or_else(|| {
+                if content.trim_start().starts_with('{') {
+                    Format::Json
+                } else {
+                    Format::Toml
+                }
+            });
+        Self::deserialize_as(&content, format)
+    }
+
+    /// Deserialize a `Report` from a string in the given [`Format`].
+    pub fn deserialize_as(content: &str, format: Format) -> Result<Self, Box<dyn Error + 'static>> {
+        let report = match format {
+            Format::Toml => toml::from_str(content)?,
+            Format::Json => serde_json::from_str(content)?,
+        };
+        Ok(report)
     }
 
     /// Serialize the `Report` to a TOML string.
     pub fn serialize(&self) -> Option<String> {
-        toml::to_string_pretty(&self).ok()
+        self.serialize_as(Format::Toml)
     }
 
-    /// Write a file to disk.
+    /// Serialize the `Report` to a string in the given [`Format`].
+    pub fn serialize_as(&self, format: Format) -> Option<String> {
+        match format {
+            Format::Toml => toml::to_string_pretty(&self).ok(),
+            Format::Json => serde_json::to_string_pretty(&self).ok(),
+        }
+    }
+
+    /// Write a TOML file to disk.
     pub fn persist(&self) -> Result<PathBuf, Box<dyn Error + 'static>> {
-        let uuid = Uuid::new_v4().hyphenated().to_string();
-        let tmp_dir = env::temp_dir();
-        let file_name = format!("report-{}.toml", &uuid);
-        let file_path = Path::new(&tmp_dir).join(file_name);
-        let toml = self.serialize().expect("only using toml-compatible types");
-        std::fs::write(&file_path, toml.as_bytes())?;
-        Ok(file_path)
+        self.persist_as(Format::Toml)
     }
-}
 
-fn render_backtrace() -> String {
-    //We take padding for address and extra two letters
-    //to pad after index.
-    #[allow(unused_qualifications)] // needed for pre-1.80 MSRV
-    const HEX_WIDTH: usize = mem::size_of::<usize>() * 2 + 2;
-    //Padding for next lines after frame's address
-    const NEXT_SYMBOL_PADDING: usize = HEX_WIDTH + 6;
+    /// Write a file to disk in the given [`Format`].
+    pub fn persist_as(&self, format: Format) -> Result<PathBuf, Box<dyn Error + 'static>> {
+        Storage::new().format(format).persist(self)
+    }
+}
 
-    let mut backtrace = String::new();
+fn capture_backtrace() -> Vec<Frame> {
+    trim_to_panic(backtrace_frames(&Backtrace::new()))
+}
 
+fn backtrace_frames(bt: &Backtrace) -> Vec<Frame> {
     //Here we iterate over backtrace frames
     //(each corresponds to function's stack)
-    //We need to print its address
+    //We need to record its address
     //and symbol(e.g. function name),
     //if it is available
-    let bt = Backtrace::new();
-    let symbols = bt
-        .frames()
+    let mut modules = None;
+    bt.frames()
         .iter()
         .flat_map(|frame| {
+            let ip = Some(format!("{:?}", frame.ip()));
             let symbols = frame.symbols();
             if symbols.is_empty() {
-                vec![(frame, None, "<unresolved>".to_owned())]
+                //Enough to symbolicate offline
+                let module = modules
+                    .get_or_insert_with(Modules::load)
+                    .find(frame.ip() as usize);
+                let module_base = frame
+                    .module_base_address()
+                    .map(|base| format!("{base:?}"))
+                    .or_else(|| module.map(|module| format!("{:#x}", module.base)));
+                vec![Frame {
+                    index: 0,
+                    ip,
+                    module_base,
+                    module: module.map(|module| module.path.clone()),
+                    name: None,
+                    file: None,
+                    line: None,
+                    column: None,
+                    inlined: false,
+                    hidden: false,
+                }]
             } else {
+                //All but the last symbol of a frame were inlined into it
+                let last = symbols.len() - 1;
                 symbols
                     .iter()
-                    .map(|s| {
-                        (
-                            frame,
-                            Some(s),
-                            s.name()
-                                .map(|n| n.to_string())
-                                .unwrap_or_else(|| "<unknown>".to_owned()),
-                        )
+                    .enumerate()
+                    .map(|(i, s)| Frame {
+                        index: 0,
+                        ip: ip.clone(),
+                        module_base: None,
+                        module: None,
+                        name: s.name().map(|n| n.to_string()),
+                        file: s.filename().map(|f| f.display().to_string()),
+                        line: s.lineno(),
+                        column: s.colno(),
+                        inlined: i != last,
+                        hidden: false,
                     })
                     .collect::<Vec<_>>()
             }
         })
+        .collect()
+}
+
+/// Skip the frames capturing the backtrace, numbering the rest
+fn trim_to_panic(frames: Vec<Frame>) -> Vec<Frame> {
+    let symbols = frames
+        .iter()
+        .map(|f| f.name.as_deref().map(normalize_symbol))
         .collect::<Vec<_>>();
-    let begin_unwind = "rust_begin_unwind";
-    let begin_unwind_start = symbols
+    let begin_unwind = symbols.iter().position(|symbol| {
+        matches!(
+            symbol.as_deref(),
+            Some("rust_begin_unwind" | "__rustc::rust_begin_unwind")
+        )
+    });
+    // Without a panic, like for errors, skip to the caller of `human_panic`
+    let start = begin_unwind.unwrap_or_else(|| {
+        symbols
+            .iter()
+            .take_while(|symbol| {
+                symbol.as_deref().is_some_and(|symbol| {
+                    CAPTURE_PREFIXES
+                        .iter()
+                        .any(|prefix| symbol.starts_with(prefix))
+                })
+            })
+            .count()
+    });
+    frames
+        .into_iter()
+        .skip(start)
+        .enumerate()
+        .map(|(index, frame)| Frame { index, ..frame })
+        .collect()
+}
+
+/// Symbols of the frames capturing a backtrace
+const CAPTURE_PREFIXES: &[&str] = &["backtrace::", "human_panic::"];
+
+/// Panics reported in this process, see [`Report::previous_panics`]
+static PANIC_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Record a panic, returning how many were recorded before it
+pub(crate) fn count_panic() -> usize {
+    PANIC_COUNT.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The panic's message, if it has one
+pub(crate) fn panic_message<'i>(panic_info: &'i PanicHookInfo<'_>) -> Option<&'i str> {
+    match (
+        panic_info.payload().downcast_ref::<&str>(),
+        panic_info.payload().downcast_ref::<String>(),
+    ) {
+        (Some(s), _) => Some(s),
+        (_, Some(s)) => Some(s),
+        (None, None) => None,
+    }
+}
+
+/// Describe a panic in one line, like `thread 'main' panicked at src/main.rs:2:5: oops`
+pub(crate) fn panic_summary(panic_info: &PanicHookInfo<'_>) -> String {
+    let thread = std::thread::current();
+    let thread = thread.name().unwrap_or("<unnamed>");
+    let message = panic_message(panic_info).unwrap_or("Unknown");
+    match panic_info.location() {
+        Some(location) => format!("thread '{thread}' panicked at {location}: {message}"),
+        None => format!("thread '{thread}' panicked: {message}"),
+    }
+}
+
+/// Extract the number from a [`ThreadId`]
+///
+/// [`ThreadId::as_u64`] is unstable, so this relies on the `Debug` output, `ThreadId(N)`.
+fn thread_id(id: ThreadId) -> Option<u64> {
+    format!("{id:?}")
+        .strip_prefix("ThreadId(")?
+        .strip_suffix(')')?
+        .parse()
+        .ok()
+}
+
+/// Parse the frames of a captured [`std::backtrace::Backtrace`]
+#[cfg(feature = "anyhow")]
+fn parse_std_backtrace(backtrace: &std::backtrace::Backtrace) -> Option<Vec<Frame>> {
+    match backtrace.status() {
+        std::backtrace::BacktraceStatus::Captured => {
+            parse_std_backtrace_str(&backtrace.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Parse the frames of a rendered [`std::backtrace::Backtrace`]
+///
+/// Frames look like:
+/// ```text
+///    0: anyhow::error::<impl anyhow::Error>::msg
+///              at /path/to/src/error.rs:83:36
+/// ```
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+fn parse_std_backtrace_str(backtrace: &str) -> Option<Vec<Frame>> {
+    let mut frames: Vec<Frame> = Vec::new();
+    for line in backtrace.lines().map(str::trim) {
+        if let Some(location) = line.strip_prefix("at ") {
+            let Some(frame) = frames.last_mut() else {
+                continue;
+            };
+            let mut parts = location.rsplitn(3, ':');
+            let (column, line, file) = (parts.next(), parts.next(), parts.next());
+            match (file, line.and_then(|l| l.parse().ok())) {
+                (Some(file), Some(line)) => {
+                    frame.file = Some(file.to_owned());
+                    frame.line = Some(line);
+                    frame.column = column.and_then(|c| c.parse().ok());
+                }
+                _ => {
+                    frame.file = Some(location.to_owned());
+                }
+            }
+        } else if let Some((index, name)) = line.split_once(": ") {
+            let Ok(index) = index.parse() else {
+                continue;
+            };
+            // `RUST_BACKTRACE=full` includes the instruction pointer
+            let (ip, name) = match name.split_once(" - ") {
+                Some((ip, name)) if ip.starts_with("0x") => (Some(ip.to_owned()), name),
+                _ => (None, name),
+            };
+            frames.push(Frame {
+                index,
+                ip,
+                module_base: None,
+                module: None,
+                name: Some(name.to_owned()),
+                file: None,
+                line: None,
+                column: None,
+                inlined: false,
+                hidden: false,
+            });
+        }
+    }
+    (!frames.is_empty()).then_some(frames)
+}
+
+/// Names of signals with the same number across unix platforms
+fn signal_name(signal: i32) -> Option<&'static str> {
+    let name = match signal {
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        4 => "SIGILL",
+        6 => "SIGABRT",
+        8 => "SIGFPE",
+        9 => "SIGKILL",
+        11 => "SIGSEGV",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Number of application frames that contribute to [`Report::fingerprint`]
+const FINGERPRINT_FRAMES: usize = 5;
+
+/// Prefixes of frames for the panic machinery, rather than the application
+pub(crate) const RUNTIME_PREFIXES: &[&str] = &[
+    "std::",
+    "core::",
+    "alloc::",
+    "backtrace::",
+    "human_panic::",
+    "__rustc::",
+    "rust_begin_unwind",
+];
+
+fn fingerprint(name: &str, explanation: &str, cause: &str, frames: &[Frame]) -> String {
+    // FNV-1a, for a hash that is stable across Rust versions and platforms
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    fn write(hash: &mut u64, part: &str) {
+        for byte in part.bytes().chain([0]) {
+            *hash ^= u64::from(byte);
+            *hash = hash.wrapping_mul(PRIME);
+        }
+    }
+
+    let mut hash = OFFSET;
+    write(&mut hash, name);
+    write(&mut hash, explanation);
+    write(&mut hash, &cause_template(cause));
+    let names = frames
         .iter()
-        .position(|(_, _, n)| n == begin_unwind)
-        .unwrap_or(0);
-    for (entry_idx, (frame, symbol, name)) in symbols.iter().skip(begin_unwind_start).enumerate() {
-        let ip = frame.ip();
-        let _ = writeln!(backtrace, "{entry_idx:4}: {ip:HEX_WIDTH$?} - {name}");
-        if let Some(symbol) = symbol {
-            //See if there is debug information with file name and line
-            if let (Some(file), Some(line)) = (symbol.filename(), symbol.lineno()) {
-                let _ = writeln!(
-                    backtrace,
-                    "{:3$}at {}:{}",
-                    "",
-                    file.display(),
-                    line,
-                    NEXT_SYMBOL_PADDING
-                );
+        .filter_map(|f| f.name.as_deref())
+        .map(normalize_symbol)
+        .filter(|name| !RUNTIME_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .take(FINGERPRINT_FRAMES);
+    for name in names {
+        write(&mut hash, &name);
+    }
+    format!("{hash:016x}")
+}
+
+/// Replace runs of digits so values embedded in a message don't change the fingerprint
+fn cause_template(cause: &str) -> String {
+    let mut template = String::with_capacity(cause.len());
+    let mut in_number = false;
+    for c in cause.chars() {
+        if c.is_ascii_digit() {
+            if !in_number {
+                template.push('N');
             }
+            in_number = true;
+        } else {
+            template.push(c);
+            in_number = false;
+        }
+    }
+    template
+}
+
+/// Strip compiler-generated hashes from a demangled symbol name
+///
+/// These change between builds, e.g. `std[e28293b1aa0f68bd]::rt::lang_start::h1b1de624209f414a`
+/// becomes `std::rt::lang_start`.
+pub(crate) fn normalize_symbol(name: &str) -> String {
+    let name = match name.rsplit_once("::h") {
+        Some((path, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            path
+        }
+        _ => name,
+    };
+    let mut normalized = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(start) = rest.find('[') {
+        normalized.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find(']') {
+            Some(end) if after[..end].chars().all(|c| c.is_ascii_hexdigit()) => {
+                rest = &after[end + 1..];
+            }
+            _ => {
+                normalized.push('[');
+                rest = after;
+            }
+        }
+    }
+    normalized.push_str(rest);
+    normalized
+}
+
+fn render_backtrace(frames: &[Frame]) -> String {
+    //We take padding for address and extra two letters
+    //to pad after index.
+    #[allow(unused_qualifications)] // needed for pre-1.80 MSRV
+    const HEX_WIDTH: usize = mem::size_of::<usize>() * 2 + 2;
+    //Padding for next lines after frame's address
+    const NEXT_SYMBOL_PADDING: usize = HEX_WIDTH + 6;
+
+    let mut backtrace = String::new();
+
+    for frame in frames.iter().filter(|frame| !frame.hidden) {
+        let Frame {
+            index, ip, name, ..
+        } = frame;
+        let ip = ip.as_deref().unwrap_or("");
+        let name = name.as_deref().unwrap_or("<unknown>");
+        let _ = writeln!(backtrace, "{index:4}: {ip:>HEX_WIDTH$} - {name}");
+        //See if there is debug information with file name and line
+        if let (Some(file), Some(line)) = (&frame.file, frame.line) {
+            let _ = writeln!(
+                backtrace,
+                "{:3$}at {}:{}",
+                "", file, line, NEXT_SYMBOL_PADDING
+            );
         }
     }
 
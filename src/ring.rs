@@ -0,0 +1,57 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError, TryLockError};
+
+/// Keeps the most recent entries, for a [`Report`][crate::report::Report]
+pub(crate) struct Ring<T> {
+    capacity: AtomicUsize,
+    entries: Mutex<VecDeque<T>>,
+}
+
+impl<T> Ring<T> {
+    pub(crate) const fn new(capacity: usize) -> Self {
+        Self {
+            capacity: AtomicUsize::new(capacity),
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Record `entry`, dropping the oldest entries beyond the capacity
+    pub(crate) fn push(&self, entry: T) {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        while capacity <= entries.len() {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Whether [`Ring::push`] would keep anything
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.capacity.load(Ordering::Relaxed) != 0
+    }
+
+    pub(crate) fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        while capacity < entries.len() {
+            entries.pop_front();
+        }
+    }
+
+    /// The recorded entries, oldest first
+    ///
+    /// This is called while reporting, so it gives up rather than waiting on a thread that is
+    /// recording an entry.
+    pub(crate) fn collect<U>(&self, f: impl FnMut(&T) -> U) -> Vec<U> {
+        let entries = match self.entries.try_lock() {
+            Ok(entries) => entries,
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) => return Vec::new(),
+        };
+        entries.iter().map(f).collect()
+    }
+}
@@ -0,0 +1,127 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::Metadata;
+use crate::report::{Frame, Snippet};
+
+/// Lines shown before and after the failing line
+const CONTEXT_LINES: u32 = 2;
+/// Frames shown, in addition to the failure's location
+const SNIPPET_FRAMES: usize = 3;
+/// Larger files are unlikely to be source code
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Source around the innermost frames from the application's own code
+///
+/// Files that can't be read, like in a release build on a user's machine, are skipped.
+pub(crate) fn frame_snippets(meta: &Metadata, frames: &[Frame]) -> Vec<Snippet> {
+    let frames = frames
+        .iter()
+        .filter(|frame| is_application(frame))
+        .filter_map(|frame| Some((frame.file()?, frame.line()?, frame.column())))
+        .take(SNIPPET_FRAMES);
+    let mut snippets: Vec<Snippet> = Vec::new();
+    for (file, line, column) in frames {
+        if snippets
+            .iter()
+            .any(|s| s.file() == file && s.line() == line)
+        {
+            continue;
+        }
+        snippets.extend(location_snippet(meta, file, line, column));
+    }
+    snippets
+}
+
+/// Source around `line` of `file`, if it can be read
+pub(crate) fn location_snippet(
+    meta: &Metadata,
+    file: &str,
+    line: u32,
+    column: Option<u32>,
+) -> Option<Snippet> {
+    let source = snippet(meta, file, line, column)?;
+    Some(Snippet::new(file.to_owned(), line, column, source))
+}
+
+fn is_application(frame: &Frame) -> bool {
+    let name = frame
+        .name()
+        .map(crate::report::normalize_symbol)
+        .unwrap_or_default();
+    let is_runtime = crate::report::RUNTIME_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix));
+    let is_dependency = frame
+        .file()
+        .map(crate::remap::is_dependency)
+        .unwrap_or(true);
+    !is_runtime && !is_dependency
+}
+
+/// Render the lines around `line` of `file`, marking `column`
+fn snippet(meta: &Metadata, file: &str, line: u32, column: Option<u32>) -> Option<String> {
+    let path = source_path(meta, file)?;
+    if MAX_FILE_BYTES < path.metadata().ok()?.len() {
+        return None;
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+
+    let first = line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = line.saturating_add(CONTEXT_LINES);
+    let width = last.to_string().len();
+    let mut snippet = String::new();
+    let lines = content.lines().zip(1..).skip(first as usize - 1);
+    for (text, number) in lines.take_while(|(_, number)| *number <= last) {
+        let _ = writeln!(snippet, "{number:>width$} | {text}");
+        if number == line
+            && let Some(column) = column
+        {
+            // Keep tabs so the marker lines up
+            let indent = text
+                .chars()
+                .take(column.saturating_sub(1) as usize)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            let _ = writeln!(snippet, "{:width$} | {indent}^", "");
+        }
+    }
+    (!snippet.is_empty()).then_some(snippet)
+}
+
+/// Find `file`, which may be relative to the workspace it was built in
+///
+/// Relative paths are never looked up in the current directory, which may hold unrelated files of
+/// the user's.
+fn source_path(meta: &Metadata, file: &str) -> Option<PathBuf> {
+    let path = Path::new(file);
+    let path = if path.is_absolute() {
+        path.to_owned()
+    } else {
+        workspace_root(meta)?.join(path)
+    };
+    path.is_file().then_some(path)
+}
+
+/// The root rustc's relative paths are from, found like Cargo does when not recorded by the build
+fn workspace_root(meta: &Metadata) -> Option<PathBuf> {
+    if let Some(root) = meta.workspace_root.as_deref() {
+        return Some(PathBuf::from(root));
+    }
+    let manifest_dir = Path::new(meta.manifest_dir.as_deref()?);
+    let root = manifest_dir
+        .ancestors()
+        .find(|dir| is_workspace_root(dir))
+        .unwrap_or(manifest_dir);
+    Some(root.to_owned())
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .map(|manifest| {
+            manifest
+                .lines()
+                .any(|line| line.trim_start().starts_with("[workspace"))
+        })
+        .unwrap_or(false)
+}
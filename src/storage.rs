@@ -0,0 +1,307 @@
+use std::env;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use uuid::Uuid;
+
+use crate::report::{Format, Report};
+
+/// Where [`Report`]s are written
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Directory {
+    /// [`std::env::temp_dir`]
+    #[default]
+    Temp,
+    /// Per-application state directory
+    ///
+    /// This is `$XDG_STATE_HOME/<name>/crash-reports`, falling back to
+    /// `~/.local/state/<name>/crash-reports` and then to [`Directory::Temp`].
+    State,
+    /// A specific directory, created if missing
+    Path(PathBuf),
+}
+
+impl Directory {
+    /// Find, and create if needed, the directory reports for `name` are written to
+    pub fn resolve(&self, name: &str) -> PathBuf {
+        match self {
+            Self::Temp => env::temp_dir(),
+            Self::State => state_dir()
+                .map(|state| state.join(name).join("crash-reports"))
+                .filter(|dir| std::fs::create_dir_all(dir).is_ok())
+                .unwrap_or_else(env::temp_dir),
+            Self::Path(dir) => {
+                let _ = std::fs::create_dir_all(dir);
+                dir.clone()
+            }
+        }
+    }
+}
+
+fn state_dir() -> Option<PathBuf> {
+    env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::home_dir().map(|home| home.join(".local").join("state")))
+}
+
+/// Limits on the reports kept in a [`Directory`]
+///
+/// When a report is written, the oldest reports beyond these limits are removed.  The new report is
+/// always kept.
+///
+/// Only the application's own reports are counted, so other files in a shared directory, like
+/// [`Directory::Temp`], are left alone.
+///
+/// ## Example
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use human_panic::report::Retention;
+///
+/// let retention = Retention::new()
+///     .max_count(20)
+///     .max_age(Duration::from_secs(30 * 24 * 60 * 60));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Retention {
+    max_count: Option<usize>,
+    max_bytes: Option<u64>,
+    max_age: Option<Duration>,
+}
+
+impl Retention {
+    /// Keep every report
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum number of reports to keep
+    pub fn max_count(mut self, count: usize) -> Self {
+        self.max_count = Some(count);
+        self
+    }
+
+    /// Maximum combined size of the reports to keep
+    pub fn max_bytes(mut self, bytes: u64) -> Self {
+        self.max_bytes = Some(bytes);
+        self
+    }
+
+    /// Remove reports older than this
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    fn is_unlimited(&self) -> bool {
+        self.max_count.is_none() && self.max_bytes.is_none() && self.max_age.is_none()
+    }
+
+    /// Remove reports for `name` from `dir` that exceed the limits, keeping `current`
+    fn apply(&self, dir: &Path, name: &str, current: &Path) {
+        if self.is_unlimited() {
+            return;
+        }
+        let Ok(entries) = dir.read_dir() else {
+            return;
+        };
+
+        let now = SystemTime::now();
+        let mut reports = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|path| path != current && is_report(path, name))
+            .filter_map(|path| {
+                let metadata = path.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(now);
+                Some((path, modified, metadata.len()))
+            })
+            .collect::<Vec<_>>();
+        // Newest first
+        reports.sort_by_key(|(_, modified, _)| std::cmp::Reverse(*modified));
+
+        let mut count = 1;
+        let mut bytes = current.metadata().map(|m| m.len()).unwrap_or(0);
+        for (path, modified, len) in reports {
+            let age = now.duration_since(modified).unwrap_or_default();
+            let keep = self.max_age.map(|max| age <= max).unwrap_or(true)
+                && self.max_count.map(|max| count < max).unwrap_or(true)
+                && self
+                    .max_bytes
+                    .map(|max| bytes.saturating_add(len) <= max)
+                    .unwrap_or(true);
+            if keep {
+                count += 1;
+                bytes += len;
+            } else {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+    }
+}
+
+/// Whether `path` is a report for `name`, like `report-<name>-<id>.toml`
+fn is_report(path: &Path, name: &str) -> bool {
+    let id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.strip_prefix("report-"))
+        .and_then(|stem| stem.strip_prefix(name))
+        .and_then(|stem| stem.strip_prefix('-'));
+    // Check the whole id, so `report-app-cli-<id>` isn't mistaken for one of `app`'s
+    let is_report_id = id
+        .map(|id| {
+            Uuid::try_parse(id).is_ok()
+                || (id.len() == 16 && id.chars().all(|c| c.is_ascii_hexdigit()))
+        })
+        .unwrap_or(false);
+    let is_report_extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Format::from_extension)
+        .is_some();
+    is_report_id && is_report_extension
+}
+
+/// How [`Report`]s are written to disk
+///
+/// ## Example
+///
+/// ```rust
+/// use human_panic::report::Directory;
+/// use human_panic::report::Format;
+/// use human_panic::report::Storage;
+///
+/// let storage = Storage::new()
+///     .directory(Directory::State)
+///     .format(Format::Json);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Storage {
+    pub(crate) directory: Directory,
+    pub(crate) format: Format,
+    retention: Retention,
+    deduplicate: bool,
+}
+
+impl Storage {
+    /// Write TOML reports to [`Directory::Temp`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Where to write reports
+    ///
+    /// By default, this is [`Directory::Temp`].
+    pub fn directory(mut self, directory: Directory) -> Self {
+        self.directory = directory;
+        self
+    }
+
+    /// Serialization format for reports
+    ///
+    /// By default, this is [`Format::Toml`].
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Limits on the reports kept in the directory
+    ///
+    /// By default, every report is kept.
+    pub fn retention(mut self, retention: Retention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Combine reports with the same [`Report::fingerprint`]
+    ///
+    /// Instead of writing a new file, the existing report's [`Report::occurrences`] and
+    /// [`Report::last_seen`] are updated.
+    ///
+    /// By default, every failure gets its own report.
+    pub fn deduplicate(mut self, yes: bool) -> Self {
+        self.deduplicate = yes;
+        self
+    }
+
+    /// Write `report` to disk, returning the path of the file
+    pub fn persist(&self, report: &Report) -> Result<PathBuf, Box<dyn Error + 'static>> {
+        let file_path = self.path_for(report);
+        self.write(&file_path, report)?;
+        Ok(file_path)
+    }
+
+    /// Where [`Storage::persist`] writes `report`
+    pub(crate) fn path_for(&self, report: &Report) -> PathBuf {
+        let dir = self.directory.resolve(report.name());
+        let id = if self.deduplicate {
+            report.fingerprint().to_owned()
+        } else {
+            Uuid::new_v4().hyphenated().to_string()
+        };
+        let file_name = format!(
+            "report-{}-{}.{}",
+            report.name(),
+            &id,
+            self.format.extension()
+        );
+        dir.join(file_name)
+    }
+
+    /// Write `report` to `file_path`, from [`Storage::path_for`]
+    ///
+    /// Returns `false` when an existing duplicate was updated instead.
+    pub(crate) fn write(
+        &self,
+        file_path: &Path,
+        report: &Report,
+    ) -> Result<bool, Box<dyn Error + 'static>> {
+        let existing = if self.deduplicate && file_path.exists() {
+            Report::load(file_path).ok().map(|mut existing| {
+                existing.recur();
+                existing
+            })
+        } else {
+            None
+        };
+        let content = existing
+            .as_ref()
+            .unwrap_or(report)
+            .serialize_as(self.format)
+            .ok_or("report could not be serialized")?;
+        std::fs::write(file_path, content.as_bytes())?;
+        if let Some(dir) = file_path.parent() {
+            self.retention.apply(dir, report.name(), file_path);
+        }
+        Ok(existing.is_none())
+    }
+
+    /// Overwrite the report at `file_path` with `report`, without deduplicating
+    pub(crate) fn replace(
+        &self,
+        file_path: &Path,
+        report: &Report,
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let content = report
+            .serialize_as(self.format)
+            .ok_or("report could not be serialized")?;
+        std::fs::write(file_path, content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Replace the report at `file_path`, previously written by [`Storage::persist`]
+    pub(crate) fn update(
+        &self,
+        file_path: &Path,
+        update: impl FnOnce(&mut Report),
+    ) -> Result<(), Box<dyn Error + 'static>> {
+        let mut report = Report::load(file_path)?;
+        update(&mut report);
+        self.replace(file_path, &report)
+    }
+}
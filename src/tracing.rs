@@ -0,0 +1,206 @@
+//! Include the active [`tracing`](https://docs.rs/tracing) spans and recent events in reports
+//!
+//! ## Example
+//!
+//! ```rust
+//! use tracing_subscriber::prelude::*;
+//!
+//! tracing_subscriber::registry()
+//!     .with(human_panic::tracing::Layer::new())
+//!     .init();
+//!
+//! human_panic::setup_panic!();
+//! ```
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, LevelFilter, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::report::Span;
+use crate::ring::Ring;
+
+static EVENTS: Ring<(SystemTime, String)> = Ring::new(100);
+
+thread_local! {
+    /// Spans entered on this thread, outermost first
+    static ENTERED: RefCell<Vec<(Id, Arc<Span>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A [`tracing_subscriber::Layer`] that tracks context for the report
+///
+/// This records the spans entered on each thread, with their fields, and keeps the most recent
+/// events.
+#[derive(Debug, Clone)]
+pub struct Layer {
+    level: LevelFilter,
+    capacity: Option<usize>,
+}
+
+impl Layer {
+    /// Keep the 100 most recent [`LevelFilter::DEBUG`] events
+    pub fn new() -> Self {
+        Self {
+            level: LevelFilter::DEBUG,
+            capacity: None,
+        }
+    }
+
+    /// Most verbose level of events to keep for the report
+    ///
+    /// This does not affect spans.
+    ///
+    /// By default, this is [`LevelFilter::DEBUG`].
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// How many events to keep for the report
+    ///
+    /// By default, this is 100.
+    pub fn capacity(mut self, count: usize) -> Self {
+        self.capacity = Some(count);
+        self
+    }
+}
+
+impl Default for Layer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> tracing_subscriber::Layer<S> for Layer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_layer(&mut self, _subscriber: &mut S) {
+        if let Some(count) = self.capacity {
+            EVENTS.set_capacity(count);
+        }
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        let metadata = attrs.metadata();
+        let record = Span::new(
+            metadata.name().to_owned(),
+            metadata.target().to_owned(),
+            fields.0,
+        );
+        span.extensions_mut().insert(Arc::new(record));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        let Some(record) = extensions.get_mut::<Arc<Span>>() else {
+            return;
+        };
+        let mut fields = Fields(record.fields().clone());
+        values.record(&mut fields);
+        let mut updated = Span::clone(record);
+        updated.set_fields(fields.0);
+        *record = Arc::new(updated);
+
+        let record = record.clone();
+        let _ = ENTERED.try_with(|entered| {
+            if let Ok(mut entered) = entered.try_borrow_mut() {
+                for (entered_id, entered_record) in entered.iter_mut() {
+                    if entered_id == id {
+                        *entered_record = record.clone();
+                    }
+                }
+            }
+        });
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(record) = span.extensions().get::<Arc<Span>>().cloned() else {
+            return;
+        };
+        let _ = ENTERED.try_with(|entered| {
+            if let Ok(mut entered) = entered.try_borrow_mut() {
+                entered.push((id.clone(), record));
+            }
+        });
+    }
+
+    fn on_exit(&self, id: &Id, _ctx: Context<'_, S>) {
+        let _ = ENTERED.try_with(|entered| {
+            if let Ok(mut entered) = entered.try_borrow_mut()
+                && let Some(index) = entered.iter().rposition(|(entered_id, _)| entered_id == id)
+            {
+                entered.remove(index);
+            }
+        });
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if self.level < *metadata.level() || !EVENTS.is_enabled() {
+            return;
+        }
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let mut fields = fields.0;
+        let mut line = format!("{:<5} {}:", metadata.level(), metadata.target());
+        if let Some(message) = fields.remove("message") {
+            let _ = write!(line, " {message}");
+        }
+        for (name, value) in fields {
+            let _ = write!(line, " {name}={value}");
+        }
+        EVENTS.push((SystemTime::now(), line));
+    }
+}
+
+#[derive(Default)]
+struct Fields(BTreeMap<String, String>);
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+}
+
+/// The spans entered on this thread, outermost first
+pub(crate) fn spans() -> Vec<Span> {
+    ENTERED
+        .try_with(|entered| {
+            entered
+                .try_borrow()
+                .map(|entered| entered.iter().map(|(_, span)| Span::clone(span)).collect())
+                .unwrap_or_default()
+        })
+        .unwrap_or_default()
+}
+
+/// The recorded events, oldest first
+pub(crate) fn events() -> Vec<String> {
+    EVENTS.collect(|(time, line)| {
+        let time = crate::time::rfc3339_millis(*time);
+        format!("{time} {line}")
+    })
+}
@@ -0,0 +1,14 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .message(|_, _| panic!("message failed"))
+        .install();
+
+    println!("A normal log message");
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
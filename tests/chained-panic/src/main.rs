@@ -0,0 +1,18 @@
+use human_panic::HookOrder;
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+fn main() {
+    std::panic::set_hook(Box::new(|_| {
+        eprintln!("Previous hook ran");
+    }));
+
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .chain(HookOrder::Before)
+        .install();
+
+    println!("A normal log message");
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
@@ -0,0 +1,86 @@
+#[test]
+fn chained() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    #[cfg(unix)]
+    let envs = [("TMPDIR", root_path)];
+    #[cfg(not(unix))]
+    let envs: [(&str, &str); 0] = [];
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("chained-panic-test"))
+        .envs(envs)
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+Previous hook ran
+chained-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+
+We have generated a report file at "[..]". Submit an issue or email with the subject of "chained-panic-test Crash Report" and include the report as an attachment.
+
+- Authors: Human Panic Authors <human-panic-crate@example.com>
+
+We take privacy seriously, and do not perform any automated error collection. In order to improve the software, we rely on people to submit reports.
+
+Thank you kindly!
+
+"#]])
+        .code(101);
+
+    root.close().unwrap();
+}
+
+#[test]
+fn chained_after() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("chained-after-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "chained-panic-test"
+...
+chained-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+...
+Thank you kindly!
+Previous hook ran
+
+"#]])
+        .code(101);
+}
+
+#[test]
+fn failing_message() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("failing-message-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "chained-panic-test"
+...
+chained-panic-test had a problem and crashed: OMG EVERYTHING IS ON FIRE!!!
+
+"#]])
+        .code(101);
+}
+
+#[test]
+fn panicking_message() {
+    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("panicking-message-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "chained-panic-test"
+...
+cause = "OMG EVERYTHING IS ON FIRE!!!"
+...
+panicked at tests/chained-panic/src/bin/panicking-message-test.rs:[..]:
+message failed
+thread panicked while processing panic. aborting.
+
+"#]])
+        .failure();
+    // std aborts on a panic within the hook, after the report was written
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt as _;
+        assert_eq!(assert.get_output().status.signal(), Some(6));
+    }
+}
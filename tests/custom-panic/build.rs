@@ -0,0 +1,3 @@
+fn main() {
+    human_panic::build::emit();
+}
@@ -0,0 +1,24 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::breadcrumb;
+use human_panic::metadata;
+
+fn main() {
+    PanicHandler::new(
+        metadata!()
+            .field("channel", "beta")
+            .fields(|| [("subcommand", std::env::args().nth(1).unwrap_or_default())]),
+    )
+    .style(PanicStyle::Human)
+    .persist(false)
+    .breadcrumbs(2)
+    .install();
+
+    breadcrumb("started");
+    breadcrumb("loaded config");
+    let subcommand = std::env::args().nth(1).unwrap_or_default();
+    breadcrumb(format!("running {subcommand}"));
+
+    println!("A normal log message");
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
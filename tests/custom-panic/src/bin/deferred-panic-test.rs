@@ -0,0 +1,14 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::Symbolication;
+use human_panic::metadata;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(std::env::args().any(|arg| arg == "--persist"))
+        .symbolication(Symbolication::Deferred)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!")
+}
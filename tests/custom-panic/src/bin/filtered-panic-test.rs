@@ -0,0 +1,17 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::FrameFilter;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .filter(FrameFilter::new().hide_crate("regex").keep_full(true))
+        .install();
+
+    let name = regex::Regex::new("[a-z]+").unwrap();
+    name.replace_all("everything", |_: &regex::Captures<'_>| -> String {
+        panic!("OMG EVERYTHING IS ON FIRE!!!")
+    });
+}
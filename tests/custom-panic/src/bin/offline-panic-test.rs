@@ -0,0 +1,18 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::Symbolication;
+use human_panic::metadata;
+use human_panic::report::Redactor;
+use human_panic::report::Remap;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .symbolication(Symbolication::Offline)
+        .remap(Remap::new())
+        .redact(Redactor::new())
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!")
+}
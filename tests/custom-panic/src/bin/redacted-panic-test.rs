@@ -0,0 +1,20 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Redactor;
+
+fn main() {
+    let token = regex::Regex::new(r"sk-[0-9a-z]+").unwrap();
+    PanicHandler::new(metadata!().field("config", "/home/alice/.config/app"))
+        .style(PanicStyle::Human)
+        .persist(false)
+        .redact(
+            Redactor::new()
+                .hostname(false)
+                .regex("token", token, "sk-<redacted>")
+                .callback("unused", |_| None),
+        )
+        .install();
+
+    panic!("alice could not authenticate with sk-4f9a2b: access denied");
+}
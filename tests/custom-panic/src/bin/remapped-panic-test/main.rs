@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Remap;
+
+mod vendor {
+    pub(crate) mod fire;
+}
+
+fn main() {
+    let vendor = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("bin")
+        .join("remapped-panic-test")
+        .join("vendor");
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .remap(Remap::new().prefix(vendor.to_str().unwrap(), "<vendor>"))
+        .install();
+
+    let name = regex::Regex::new("[a-z]+").unwrap();
+    name.replace_all("everything", |_: &regex::Captures<'_>| -> String {
+        vendor::fire::fire()
+    });
+}
@@ -24,6 +24,34 @@ Thank you kindly!
         .code(101);
 }
 
+#[test]
+fn report_context() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("context-panic-test"))
+        .arg("deploy")
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+...
+occurrences = 1
+
+[custom]
+channel = "beta"
+subcommand = "deploy"
+...
+[[breadcrumbs]]
+timestamp = "[..]"
+message = "loaded config"
+
+[[breadcrumbs]]
+timestamp = "[..]"
+message = "running deploy"
+
+[[backtrace]]
+...
+"#]])
+        .code(101);
+}
+
 #[test]
 #[cfg_attr(not(debug_assertions), ignore)]
 fn debug() {
@@ -39,3 +67,186 @@ note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
 "#]])
         .code(101);
 }
+
+#[test]
+fn redacted() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("redacted-panic-test"))
+        .env("HOME", "/home/alice")
+        .env("USERPROFILE", "/home/alice")
+        .env("USER", "alice")
+        .env("USERNAME", "alice")
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "custom-panic-test"
+...
+cause = "<user> could not authenticate with sk-<redacted>: access denied"
+...
+redactions = [
+    "home",
+    "username",
+    "token",
+]
+
+[custom]
+config = "~/.config/app"
+...
+[[backtrace]]
+...
+"#]])
+        .code(101);
+}
+
+#[test]
+#[cfg_attr(not(debug_assertions), ignore)]
+fn remapped() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("remapped-panic-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "custom-panic-test"
+...
+file = "<rust>/library/core/src/panicking.rs"
+...
+file = "<vendor>/fire.rs"
+...
+file = "tests/custom-panic/src/bin/remapped-panic-test/main.rs"
+...
+file = "regex-[..]/src/regex/string.rs"
+...
+"#]])
+        .code(101);
+}
+
+#[test]
+#[cfg_attr(not(debug_assertions), ignore)]
+fn filtered() {
+    let output = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("filtered-panic-test"))
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let (report, _) = stderr
+        .split_once("\ncustom-panic-test had a problem")
+        .unwrap();
+    let report =
+        human_panic::report::Report::deserialize_as(report, human_panic::report::Format::Toml)
+            .unwrap();
+    assert!(report.backtrace().iter().any(|frame| frame.is_hidden()));
+    snapbox::assert_data_eq!(
+        report.render_backtrace(),
+        snapbox::str![[r#"
+[..]: [..] - filtered_panic_test::main::{{closure}}::h[..]
+[..]at [..]filtered-panic-test.rs:15
+[..]: [..] - filtered_panic_test::main::h[..]
+[..]at [..]filtered-panic-test.rs:14
+
+"#]]
+    );
+}
+
+#[test]
+fn offline() {
+    let bin = snapbox::cmd::cargo_bin!("offline-panic-test");
+    // Where the binary lives, standing in for the user's home directory
+    let home = bin.parent().unwrap().to_str().unwrap().to_owned();
+    let output = snapbox::cmd::Command::new(bin)
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let (report, _) = stderr
+        .split_once("\ncustom-panic-test had a problem")
+        .unwrap();
+    let report =
+        human_panic::report::Report::deserialize_as(report, human_panic::report::Format::Toml)
+            .unwrap();
+    assert!(!report.backtrace().is_empty());
+    assert!(
+        report
+            .backtrace()
+            .iter()
+            .all(|frame| frame.name().is_none())
+    );
+    assert!(report.backtrace().iter().all(|frame| frame.ip().is_some()));
+    assert!(
+        report
+            .backtrace()
+            .iter()
+            .any(|frame| frame.module_base().is_some() && frame.module().is_some())
+    );
+    let modules = report
+        .backtrace()
+        .iter()
+        .filter_map(|frame| frame.module())
+        .collect::<Vec<_>>();
+    assert!(
+        modules
+            .iter()
+            .any(|module| module.ends_with("offline-panic-test")),
+        "{modules:?}"
+    );
+    assert!(
+        !modules.iter().any(|module| module.contains(home.as_str())),
+        "{modules:?}"
+    );
+}
+
+#[test]
+fn deferred() {
+    let output = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("deferred-panic-test"))
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    // The message is shown before symbols are resolved
+    let (message, report) = stderr.split_once("Thank you kindly!\n").unwrap();
+    assert!(message.starts_with("custom-panic-test had a problem"));
+    let report =
+        human_panic::report::Report::deserialize_as(report, human_panic::report::Format::Toml)
+            .unwrap();
+    assert!(
+        report
+            .backtrace()
+            .iter()
+            .filter_map(|frame| frame.name())
+            .any(|name| name.starts_with("deferred_panic_test::main"))
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn deferred_persisted() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    let output = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("deferred-panic-test"))
+        .arg("--persist")
+        .env("TMPDIR", root_path)
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let (_, path) = stderr.split_once("report file at \"").unwrap();
+    let (path, _) = path.split_once('"').unwrap();
+    let files = root_path
+        .read_dir()
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(files, [std::path::PathBuf::from(path)]);
+    // The report written before resolving symbols is replaced by the resolved one
+    let report = human_panic::report::Report::load(path).unwrap();
+    assert_eq!(report.occurrences(), 1);
+    assert!(
+        report
+            .backtrace()
+            .iter()
+            .filter_map(|frame| frame.name())
+            .any(|name| name.starts_with("deferred_panic_test::main"))
+    );
+
+    root.close().unwrap();
+}
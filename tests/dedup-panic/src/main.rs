@@ -0,0 +1,12 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .deduplicate(true)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
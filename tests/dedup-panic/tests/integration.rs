@@ -0,0 +1,38 @@
+#[test]
+#[cfg(unix)]
+fn deduplicated() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    let run = || {
+        snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("dedup-panic-test"))
+            .env("TMPDIR", root_path)
+            .env_remove("CI")
+            .assert()
+            .code(101);
+        root_path
+            .read_dir()
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect::<Vec<_>>()
+    };
+
+    let first = run();
+    assert_eq!(first.len(), 1, "{first:?}");
+    let report = human_panic::report::Report::load(&first[0]).unwrap();
+    assert_eq!(report.occurrences(), 1);
+    assert_eq!(report.last_seen(), None);
+
+    let second = run();
+    assert_eq!(second, first);
+    let report = human_panic::report::Report::load(&second[0]).unwrap();
+    assert_eq!(report.occurrences(), 2);
+    assert!(report.last_seen().is_some());
+    let file_name = second[0].file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        file_name,
+        format!("report-dedup-panic-test-{}.toml", report.fingerprint())
+    );
+
+    root.close().unwrap();
+}
@@ -0,0 +1,27 @@
+use std::process::ExitCode;
+
+use eyre::WrapErr as _;
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+fn main() -> ExitCode {
+    let handler = PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false);
+    handler.clone().install();
+
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            handler.report_eyre(&error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> eyre::Result<()> {
+    println!("A normal log message");
+    std::fs::read_to_string("does-not-exist/config.toml").wrap_err("failed to load config")?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use std::process::ExitCode;
+
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+#[derive(Debug)]
+struct ConfigError(std::io::Error);
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to load config")
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn main() -> ExitCode {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .run(|| {
+            println!("A normal log message");
+            Err(ConfigError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "config.toml does not exist",
+            )))
+        })
+}
@@ -0,0 +1,203 @@
+#[test]
+fn error() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("error-report-test"))
+        .env_remove("CI")
+        .assert()
+        .stdout_eq(snapbox::str![[r#"
+A normal log message
+
+"#]])
+        .stderr_eq(snapbox::str![[r#"
+name = "error-report-test"
+operating_system = "[..]"
+crate_version = "0.1.0"
+timestamp = "[..]"
+uptime_ms = [..]
+pid = [..]
+explanation = """
+Error returned from `main`
+"""
+cause = "failed to load config"
+causes = ["config.toml does not exist"]
+method = "Error"
+thread_name = "main"
+thread_id = [..]
+fingerprint = "[..]"
+occurrences = 1
+...
+[[backtrace]]
+...
+error-report-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+
+- Authors: Human Panic Authors <human-panic-crate@example.com>
+
+We take privacy seriously, and do not perform any automated error collection. In order to improve the software, we rely on people to submit reports.
+
+Thank you kindly!
+
+"#]])
+        .code(1);
+}
+
+#[test]
+fn anyhow() {
+    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("anyhow-report-test"))
+        .env_remove("CI")
+        .env("RUST_LIB_BACKTRACE", "1")
+        .assert()
+        .stdout_eq(snapbox::str![[r#"
+A normal log message
+
+"#]])
+        .stderr_eq(snapbox::str![[r#"
+name = "error-report-test"
+operating_system = "[..]"
+crate_version = "0.1.0"
+timestamp = "[..]"
+uptime_ms = [..]
+pid = [..]
+explanation = """
+Error returned from `main`
+"""
+cause = "failed to load config"
+causes = ["[..]"]
+method = "Error"
+thread_name = "main"
+thread_id = [..]
+fingerprint = "[..]"
+occurrences = 1
+...
+[[backtrace]]
+index = 0
+name = "[..]"
+...
+error-report-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+
+- Authors: Human Panic Authors <human-panic-crate@example.com>
+
+We take privacy seriously, and do not perform any automated error collection. In order to improve the software, we rely on people to submit reports.
+
+Thank you kindly!
+
+"#]])
+        .code(1);
+    assert_own_backtrace(&assert.get_output().stderr, "anyhow");
+}
+
+#[test]
+fn eyre() {
+    let assert = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("eyre-report-test"))
+        .env_remove("CI")
+        .env("RUST_LIB_BACKTRACE", "1")
+        .assert()
+        .stdout_eq(snapbox::str![[r#"
+A normal log message
+
+"#]])
+        .stderr_eq(snapbox::str![[r#"
+name = "error-report-test"
+operating_system = "[..]"
+crate_version = "0.1.0"
+timestamp = "[..]"
+uptime_ms = [..]
+pid = [..]
+explanation = """
+Error returned from `main`
+"""
+cause = "failed to load config"
+causes = ["[..]"]
+method = "Error"
+thread_name = "main"
+thread_id = [..]
+fingerprint = "[..]"
+occurrences = 1
+...
+[[backtrace]]
+index = 0
+name = "[..]"
+...
+error-report-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+
+- Authors: Human Panic Authors <human-panic-crate@example.com>
+
+We take privacy seriously, and do not perform any automated error collection. In order to improve the software, we rely on people to submit reports.
+
+Thank you kindly!
+
+"#]])
+        .code(1);
+    assert_own_backtrace(&assert.get_output().stderr, "eyre");
+}
+
+/// Check the report's frames are from where `error_crate` created the error, rather than where it
+/// was reported
+fn assert_own_backtrace(stderr: &[u8], error_crate: &str) {
+    let stderr = std::str::from_utf8(stderr).unwrap();
+    let (report, _) = stderr
+        .split_once("\nerror-report-test had a problem")
+        .unwrap();
+    let report =
+        human_panic::report::Report::deserialize_as(report, human_panic::report::Format::Toml)
+            .unwrap();
+    let names = report
+        .backtrace()
+        .iter()
+        .filter_map(|frame| frame.name())
+        .collect::<Vec<_>>();
+    let in_error_crate = format!("{error_crate}::");
+    assert!(
+        names.iter().any(|name| name.contains(&in_error_crate)),
+        "{names:?}"
+    );
+    assert!(
+        !names.iter().any(|name| name.starts_with("human_panic")),
+        "{names:?}"
+    );
+}
+
+#[test]
+fn fingerprint_per_application() {
+    let error = std::io::Error::other("failed to load config");
+    let report = |name: &str| {
+        let meta = human_panic::Metadata::new(name.to_owned(), "0.1.0".to_owned());
+        human_panic::report::Report::from_error(&meta, &error)
+    };
+    assert_eq!(report("first").fingerprint(), report("first").fingerprint());
+    assert_ne!(
+        report("first").fingerprint(),
+        report("second").fingerprint()
+    );
+}
+
+#[test]
+fn fatal_methods() {
+    use human_panic::report::{Format, Method, Report};
+
+    let meta = human_panic::Metadata::new("error-report-test", "0.1.0");
+    let layout = std::alloc::Layout::from_size_align(1024, 8).unwrap();
+    let reports = [
+        (Report::with_signal(&meta, 11), Method::Signal, "SIGSEGV"),
+        (Report::with_signal(&meta, 64), Method::Signal, "signal 64"),
+        (
+            Report::with_abort(&meta, "assertion failed in allocator"),
+            Method::Abort,
+            "assertion failed in allocator",
+        ),
+        (
+            Report::with_alloc_error(&meta, layout),
+            Method::OutOfMemory,
+            "memory allocation of 1024 bytes failed",
+        ),
+    ];
+    for (report, method, cause) in reports {
+        assert_eq!(report.method(), method);
+        assert_eq!(report.cause(), cause);
+        for format in [Format::Toml, Format::Json] {
+            let serialized = report.serialize_as(format).unwrap();
+            let loaded = Report::deserialize_as(&serialized, format).unwrap();
+            assert_eq!(loaded.method(), method, "{serialized}");
+            assert_eq!(loaded.cause(), cause, "{serialized}");
+            assert_eq!(loaded.explanation(), report.explanation(), "{serialized}");
+        }
+    }
+}
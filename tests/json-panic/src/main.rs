@@ -0,0 +1,13 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Format;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .format(Format::Json)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
@@ -0,0 +1,51 @@
+#[test]
+#[cfg(unix)]
+fn json() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("json-panic-test"))
+        .env("TMPDIR", root_path)
+        .env_remove("CI")
+        .assert()
+        .code(101);
+
+    let mut files = root_path
+        .read_dir()
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 1, "{files:?}");
+    let path = files.pop().unwrap();
+    assert_eq!(path.extension().unwrap(), "json");
+    let loaded = human_panic::report::Report::load(&path).unwrap();
+    assert_eq!(loaded.name(), "json-panic-test");
+    assert_eq!(loaded.cause(), "OMG EVERYTHING IS ON FIRE!!!");
+    snapbox::assert_data_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        snapbox::str![[r#"
+{
+  "name": "json-panic-test",
+  "operating_system": "[..]",
+  "crate_version": "0.1.0",
+  "timestamp": "[..]",
+  "uptime_ms": [..],
+  "pid": [..],
+  "explanation": "Panic occurred in file 'tests/json-panic/src/main.rs' at line [..]",
+  "cause": "OMG EVERYTHING IS ON FIRE!!!",
+  "method": "Panic",
+  "thread_name": "main",
+  "thread_id": [..],
+  "previous_panics": 0,
+  "fingerprint": "[..]",
+  "occurrences": 1,
+...
+  "backtrace": [
+...
+  ]
+}
+"#]]
+    );
+
+    root.close().unwrap();
+}
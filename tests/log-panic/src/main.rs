@@ -0,0 +1,33 @@
+use human_panic::log::Logger;
+use human_panic::setup_panic;
+use log::LevelFilter;
+
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, _metadata: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        eprintln!("[{}] {}", record.level(), record.args());
+    }
+
+    fn flush(&self) {}
+}
+
+fn main() {
+    Logger::new()
+        .capacity(2)
+        .forward(StderrLogger, LevelFilter::Warn)
+        .install()
+        .unwrap();
+    setup_panic!();
+
+    log::trace!("not kept");
+    log::debug!("opening {}", "config.toml");
+    log::info!("config loaded");
+    log::warn!("cache is stale");
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
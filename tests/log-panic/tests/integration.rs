@@ -0,0 +1,35 @@
+#[test]
+#[cfg_attr(debug_assertions, ignore)]
+fn release() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("log-panic-test"))
+        .env("CI", "1")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+[WARN] cache is stale
+name = "log-panic-test"
+...
+logs = [
+    "[..] INFO  log_panic_test: config loaded",
+    "[..] WARN  log_panic_test: cache is stale",
+]
+...
+"#]])
+        .code(101);
+}
+
+#[test]
+#[cfg_attr(not(debug_assertions), ignore)]
+fn debug() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("log-panic-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+[WARN] cache is stale
+
+thread 'main' ([..]) panicked at tests/log-panic/src/main.rs:32:5:
+OMG EVERYTHING IS ON FIRE!!!
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+"#]])
+        .code(101);
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Retention;
+
+fn main() {
+    // Usage: retention-panic-test <count|bytes|age> <limit>
+    let mut args = std::env::args().skip(1);
+    let kind = args.next().unwrap_or_default();
+    let limit = args
+        .next()
+        .and_then(|limit| limit.parse::<u64>().ok())
+        .unwrap_or(0);
+    let retention = match kind.as_str() {
+        "count" => Retention::new().max_count(limit as usize),
+        "bytes" => Retention::new().max_bytes(limit),
+        "age" => Retention::new().max_age(Duration::from_secs(limit)),
+        _ => Retention::new(),
+    };
+
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .retention(retention)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
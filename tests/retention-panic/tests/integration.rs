@@ -0,0 +1,119 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Files that aren't this application's reports, and must be kept
+const UNRELATED: &[&str] = &[
+    "report-other-app-7c2fdf8e-3a8e-4d4b-9b43-5d1f2f6c4a10.toml",
+    "report-retention-panic-test-cli-1f0c5a2e-9d7b-4e6a-8c3d-2b1a0f9e8d7c.toml",
+    "notes.toml",
+];
+
+#[test]
+#[cfg(unix)]
+fn max_count() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+    let unrelated = seed(root_path, Duration::ZERO);
+
+    let reports = (0..4)
+        .map(|_| run(root_path, &["count", "2"]))
+        .collect::<Vec<_>>();
+
+    let expected = reports[2..]
+        .iter()
+        .cloned()
+        .chain(unrelated)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(files(root_path), expected);
+
+    root.close().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn max_bytes() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+    let unrelated = seed(root_path, Duration::ZERO);
+
+    let unlimited = u64::MAX.to_string();
+    let first = run(root_path, &["bytes", &unlimited]);
+    let second = run(root_path, &["bytes", &unlimited]);
+    // Room for two reports, but not three
+    let len = first.metadata().unwrap().len();
+    let limit = (len * 5 / 2).to_string();
+    let third = run(root_path, &["bytes", &limit]);
+
+    let expected = [second, third]
+        .into_iter()
+        .chain(unrelated)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(files(root_path), expected);
+
+    root.close().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn max_age() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+    let two_hours = Duration::from_secs(2 * 60 * 60);
+    let unrelated = seed(root_path, two_hours);
+    let old =
+        root_path.join("report-retention-panic-test-0a4e8f3c-6b2d-4f1e-9c7a-3d5b2e1f0a9b.toml");
+    write_old(&old, two_hours);
+    let recent =
+        root_path.join("report-retention-panic-test-5e6f7a8b-9c0d-4e1f-8a2b-3c4d5e6f7a8b.toml");
+    write_old(&recent, Duration::from_secs(60));
+
+    let report = run(root_path, &["age", "3600"]);
+
+    let expected = [recent, report]
+        .into_iter()
+        .chain(unrelated)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(files(root_path), expected);
+
+    root.close().unwrap();
+}
+
+/// Run the fixture with `dir` as the temp dir, returning the report it wrote
+fn run(dir: &Path, args: &[&str]) -> PathBuf {
+    let output = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("retention-panic-test"))
+        .args(args)
+        .env("TMPDIR", dir)
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(101));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let (_, path) = stderr.split_once("report file at \"").unwrap();
+    let (path, _) = path.split_once('"').unwrap();
+    // Keep the modification times of consecutive reports apart
+    std::thread::sleep(Duration::from_millis(20));
+    PathBuf::from(path)
+}
+
+/// Write [`UNRELATED`] files to `dir`, last modified `age` ago
+fn seed(dir: &Path, age: Duration) -> Vec<PathBuf> {
+    UNRELATED
+        .iter()
+        .map(|name| {
+            let path = dir.join(name);
+            write_old(&path, age);
+            path
+        })
+        .collect()
+}
+
+fn write_old(path: &Path, age: Duration) {
+    std::fs::write(path, "name = \"other\"\n").unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+fn files(dir: &Path) -> BTreeSet<PathBuf> {
+    dir.read_dir().unwrap().map(|e| e.unwrap().path()).collect()
+}
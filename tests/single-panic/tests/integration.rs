@@ -40,23 +40,71 @@ Thank you kindly!
             })
             .collect::<Vec<_>>();
         assert_eq!(files.len(), 1, "{files:?}");
-        let (_, report) = files.pop().unwrap();
+        let (path, report) = files.pop().unwrap();
         let report = report.unwrap();
+        let loaded = human_panic::report::Report::load(&path).unwrap();
+        assert_eq!(loaded.cause(), "OMG EVERYTHING IS ON FIRE!!!");
+        assert_eq!(loaded.build().features(), ["default", "extra_logging"]);
+        let head = std::process::Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success());
+        if let Some(head) = head {
+            let head = String::from_utf8(head.stdout).unwrap();
+            assert_eq!(loaded.build().git_commit(), Some(head.trim()));
+        }
+        let names = loaded
+            .backtrace()
+            .iter()
+            .filter_map(|frame| frame.name())
+            .collect::<Vec<_>>();
+        assert!(names[0].ends_with("rust_begin_unwind"), "{names:?}");
+        assert!(
+            !names.iter().any(|name| name.starts_with("human_panic")),
+            "{names:?}"
+        );
         snapbox::assert_data_eq!(
             report,
             snapbox::str![[r#"
 name = "single-panic-test"
 operating_system = "[..]"
 crate_version = "0.1.0"
+timestamp = "[..]"
+uptime_ms = [..]
+pid = [..]
 explanation = """
 Panic occurred in file 'tests/single-panic/src/main.rs' at line [..]
 """
 cause = "OMG EVERYTHING IS ON FIRE!!!"
 method = "Panic"
-backtrace = """
+thread_name = "main"
+thread_id = [..]
+previous_panics = 0
+fingerprint = "[..]"
+occurrences = 1
+
+[build]
+rustc_version = "[..]"
+target = "[..]"
+profile = "release"
+opt_level = "3"
 ...
+[[snippets]]
+file = "tests/single-panic/src/main.rs"
+line = 27
+column = 5
+source = """
+25 | fn do_panic() {
+26 |     println!("A normal log message");
+27 |     panic!("OMG EVERYTHING IS ON FIRE!!!");
+   |     ^
+28 | }
 """
 
+[[backtrace]]
+...
+
 "#]]
         );
     }
@@ -128,14 +176,40 @@ fn release_with_ci() {
 name = "single-panic-test"
 operating_system = "[..]"
 crate_version = "0.1.0"
+timestamp = "[..]"
+uptime_ms = [..]
+pid = [..]
 explanation = [..]
 Panic occurred in file 'tests/single-panic/src/main.rs' at line 27
 [..]
 cause = "OMG EVERYTHING IS ON FIRE!!!"
 method = "Panic"
-backtrace = [..]
+thread_name = "main"
+thread_id = [..]
+previous_panics = 0
+fingerprint = "[..]"
+occurrences = 1
+
+[build]
+rustc_version = "[..]"
+target = "[..]"
+profile = "release"
+opt_level = "3"
 ...
+[[snippets]]
+file = "tests/single-panic/src/main.rs"
+line = 27
+column = 5
+source = """
+25 | fn do_panic() {
+26 |     println!("A normal log message");
+27 |     panic!("OMG EVERYTHING IS ON FIRE!!!");
+   |     ^
+28 | }
+"""
 
+[[backtrace]]
+...
 single-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
 
 - Authors: Human Panic Authors <human-panic-crate@example.com>
@@ -0,0 +1,13 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+
+fn main() {
+    // Without `build::emit`, so without a recorded workspace root
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .persist(false)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
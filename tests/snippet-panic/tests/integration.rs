@@ -0,0 +1,23 @@
+#[test]
+fn snippet() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("snippet-panic-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "snippet-panic-test"
+...
+[[snippets]]
+file = "tests/snippet-panic/src/main.rs"
+line = 12
+column = 5
+source = """
+10 |         .install();
+11 | 
+12 |     panic!("OMG EVERYTHING IS ON FIRE!!!");
+   |     ^
+13 | }
+"""
+...
+"#]])
+        .code(101);
+}
@@ -0,0 +1,13 @@
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Directory;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .directory(Directory::State)
+        .install();
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+#[test]
+fn xdg_state_home() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    let report = run(&[("XDG_STATE_HOME", root_path)]);
+
+    let dir = root_path.join("state-panic-test").join("crash-reports");
+    assert_eq!(report.parent(), Some(dir.as_path()));
+    assert_eq!(reports(&dir), [report]);
+
+    root.close().unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn home_fallback() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    // Relative state dirs are ignored, as the XDG spec requires
+    let report = run(&[("XDG_STATE_HOME", Path::new("state")), ("HOME", root_path)]);
+
+    let dir = root_path
+        .join(".local")
+        .join("state")
+        .join("state-panic-test")
+        .join("crash-reports");
+    assert_eq!(report.parent(), Some(dir.as_path()));
+    assert_eq!(reports(&dir), [report]);
+
+    root.close().unwrap();
+}
+
+/// Run the fixture with `envs`, returning the report it wrote
+fn run(envs: &[(&str, &Path)]) -> PathBuf {
+    let output = snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("state-panic-test"))
+        .envs(envs.iter().copied())
+        .env_remove("CI")
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(101));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let (_, path) = stderr.split_once("report file at \"").unwrap();
+    let (path, _) = path.split_once('"').unwrap();
+    PathBuf::from(path)
+}
+
+fn reports(dir: &Path) -> Vec<PathBuf> {
+    dir.read_dir().unwrap().map(|e| e.unwrap().path()).collect()
+}
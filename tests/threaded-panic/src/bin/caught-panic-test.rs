@@ -0,0 +1,18 @@
+use std::time::Duration;
+
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Directory;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .directory(Directory::State)
+        .install();
+
+    // Like a REPL recovering from a failed command
+    let _ = std::panic::catch_unwind(|| panic!("first command failed"));
+    std::thread::sleep(Duration::from_millis(1500));
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
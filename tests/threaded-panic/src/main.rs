@@ -0,0 +1,38 @@
+use std::sync::Arc;
+use std::sync::Barrier;
+
+use human_panic::PanicHandler;
+use human_panic::PanicStyle;
+use human_panic::metadata;
+use human_panic::report::Directory;
+
+fn main() {
+    PanicHandler::new(metadata!())
+        .style(PanicStyle::Human)
+        .directory(Directory::State)
+        .install();
+
+    let barrier = Arc::new(Barrier::new(3));
+    let workers = (0..3)
+        .map(|i| {
+            let barrier = barrier.clone();
+            std::thread::Builder::new()
+                .name(format!("worker-{i}"))
+                .spawn(move || {
+                    barrier.wait();
+                    panic!("OMG EVERYTHING IS ON FIRE!!!");
+                })
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    println!("A normal log message");
+    let failed = workers
+        .into_iter()
+        .map(|worker| worker.join())
+        .filter(Result::is_err)
+        .count();
+    if failed != 0 {
+        panic!("{failed} workers failed");
+    }
+}
@@ -0,0 +1,80 @@
+#[test]
+fn threaded() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("threaded-panic-test"))
+        .env("XDG_STATE_HOME", root_path)
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+threaded-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+
+We have generated a report file at "[..]". Submit an issue or email with the subject of "threaded-panic-test Crash Report" and include the report as an attachment.
+
+- Authors: Human Panic Authors <human-panic-crate@example.com>
+
+We take privacy seriously, and do not perform any automated error collection. In order to improve the software, we rely on people to submit reports.
+
+Thank you kindly!
+threaded-panic-test: additional thread 'worker-[..]' panicked at tests/threaded-panic/src/main.rs:[..]: OMG EVERYTHING IS ON FIRE!!!
+threaded-panic-test: additional thread 'worker-[..]' panicked at tests/threaded-panic/src/main.rs:[..]: OMG EVERYTHING IS ON FIRE!!!
+threaded-panic-test: additional thread 'main' panicked at tests/threaded-panic/src/main.rs:[..]: 3 workers failed
+
+"#]])
+        .code(101);
+
+    let mut files = root_path
+        .join("threaded-panic-test")
+        .join("crash-reports")
+        .read_dir()
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(files.len(), 1, "{files:?}");
+    let report = human_panic::report::Report::load(files.pop().unwrap()).unwrap();
+    assert_eq!(report.previous_panics(), Some(0));
+    assert_eq!(report.secondary_panics().len(), 3, "{report:?}");
+
+    root.close().unwrap();
+}
+
+#[test]
+fn caught() {
+    let root = snapbox::dir::DirRoot::mutable_temp().unwrap();
+    let root_path = root.path().unwrap();
+
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("caught-panic-test"))
+        .env("XDG_STATE_HOME", root_path)
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+threaded-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+...
+Thank you kindly!
+threaded-panic-test had a problem and crashed. To help us diagnose the problem you can send us a crash report.
+...
+Thank you kindly!
+
+"#]])
+        .code(101);
+
+    let mut causes = root_path
+        .join("threaded-panic-test")
+        .join("crash-reports")
+        .read_dir()
+        .unwrap()
+        .map(|e| {
+            let report = human_panic::report::Report::load(e.unwrap().path()).unwrap();
+            assert!(report.secondary_panics().is_empty(), "{report:?}");
+            report.cause().to_owned()
+        })
+        .collect::<Vec<_>>();
+    causes.sort();
+    assert_eq!(
+        causes,
+        ["OMG EVERYTHING IS ON FIRE!!!", "first command failed"]
+    );
+
+    root.close().unwrap();
+}
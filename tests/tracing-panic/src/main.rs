@@ -0,0 +1,23 @@
+use human_panic::setup_panic;
+use tracing_subscriber::prelude::*;
+
+fn main() {
+    tracing_subscriber::registry()
+        .with(human_panic::tracing::Layer::new().capacity(2))
+        .init();
+    setup_panic!();
+
+    tracing::trace!("not kept");
+    tracing::debug!("starting");
+    let _request = tracing::info_span!("request", id = 7, user = "alice").entered();
+    tracing::info!(attempt = 2, "loading config");
+    {
+        let _done = tracing::info_span!("done").entered();
+    }
+    let file = tracing::debug_span!("file", path = "config.toml", size = tracing::field::Empty);
+    let _file = file.enter();
+    file.record("size", 512);
+    tracing::warn!("config is empty");
+
+    panic!("OMG EVERYTHING IS ON FIRE!!!");
+}
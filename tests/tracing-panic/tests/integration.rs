@@ -0,0 +1,51 @@
+#[test]
+#[cfg_attr(debug_assertions, ignore)]
+fn release() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("tracing-panic-test"))
+        .env("CI", "1")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+name = "tracing-panic-test"
+...
+events = [
+    "[..] INFO  tracing_panic_test: loading config attempt=2",
+    "[..] WARN  tracing_panic_test: config is empty",
+]
+
+[[spans]]
+name = "request"
+target = "tracing_panic_test"
+
+[spans.fields]
+id = "7"
+user = "alice"
+
+[[spans]]
+name = "file"
+target = "tracing_panic_test"
+
+[spans.fields]
+path = "config.toml"
+size = "512"
+
+[[backtrace]]
+...
+"#]])
+        .code(101);
+}
+
+#[test]
+#[cfg_attr(not(debug_assertions), ignore)]
+fn debug() {
+    snapbox::cmd::Command::new(snapbox::cmd::cargo_bin!("tracing-panic-test"))
+        .env_remove("CI")
+        .assert()
+        .stderr_eq(snapbox::str![[r#"
+
+thread 'main' ([..]) panicked at tests/tracing-panic/src/main.rs:22:5:
+OMG EVERYTHING IS ON FIRE!!!
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+"#]])
+        .code(101);
+}